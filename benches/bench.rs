@@ -3,131 +3,194 @@ use criterion::black_box;
 
 use aoc_2020::AoCDay;
 use aoc_2020::SinglePart;
+use aoc_2020::input;
+use aoc_2020::intcode::{memory_from_str, Engine, IntCodeVM, Memory};
+
+fn load(year: u32, day: u32) -> String {
+	input::load(None, year, day).expect("Benchmarks need the puzzle inputs in place")
+}
 
 pub fn nineteen01(c: &mut Criterion) {
 	use aoc_2020::nineteen01::Code;
 	let code: Code = Code {};
-	c.bench_function("2019-01 Part 1", |b| b.iter(|| code.part1()));
-	c.bench_function("2019-01 Part 2", |b| b.iter(|| code.part2()));
+	let input = code.parse(&load(2019, 1)).unwrap();
+	c.bench_function("2019-01 Part 1", |b| b.iter(|| code.part1(&input)));
+	c.bench_function("2019-01 Part 2", |b| b.iter(|| code.part2(&input)));
 }
 
 pub fn nineteen02(c: &mut Criterion) {
 	use aoc_2020::nineteen02::Code;
 	let code: Code = Code {};
-	c.bench_function("2019-02 Part 1", |b| b.iter(|| code.part1()));
-	c.bench_function("2019-02 Part 2", |b| b.iter(|| code.part2()));
+	let input = code.parse(&load(2019, 2)).unwrap();
+	c.bench_function("2019-02 Part 1", |b| b.iter(|| code.part1(&input)));
+	c.bench_function("2019-02 Part 2", |b| b.iter(|| code.part2(&input)));
 }
 
 pub fn nineteen03(c: &mut Criterion) {
 	use aoc_2020::nineteen03::Code;
 	let code: Code = Code {};
-	c.bench_function("2019-03 Only Part", |b| b.iter(|| code.run()));
+	let input = code.parse(&load(2019, 3)).unwrap();
+	c.bench_function("2019-03 Only Part", |b| b.iter(|| code.run(&input)));
 }
 
 pub fn nineteen04(c: &mut Criterion) {
-	use aoc_2020::nineteen04::count_passwords_between;
-	c.bench_function("2019-04 Action function", |b| b.iter(|| count_passwords_between(black_box(168630), black_box(718098))));
+	use aoc_2020::nineteen04::{count_passwords_between, parse_range};
+	let (min, max) = parse_range(&load(2019, 4)).unwrap();
+	c.bench_function("2019-04 Action function", |b| b.iter(|| count_passwords_between(black_box(min), black_box(max))));
 }
 
 pub fn nineteen05(c: &mut Criterion) {
 	use aoc_2020::nineteen05::{Code};
 	let code: Code = Code {};
-	c.bench_function("2019-05 Part 1", |b| b.iter(|| code.part1()));
-	c.bench_function("2019-05 Part 2", |b| b.iter(|| code.part2()));
+	let input = code.parse(&load(2019, 5)).unwrap();
+	c.bench_function("2019-05 Part 1", |b| b.iter(|| code.part1(&input)));
+	c.bench_function("2019-05 Part 2", |b| b.iter(|| code.part2(&input)));
 }
 
 pub fn nineteen06(c: &mut Criterion) {
-	use aoc_2020::nineteen06::{part_1_impl, part_2_impl, FL_CONT};
-	c.bench_function("2019-06 Part 1", |b| b.iter(|| part_1_impl(black_box(FL_CONT))));
-	c.bench_function("2019-06 Part 2", |b| b.iter(|| part_2_impl(black_box(FL_CONT))));
+	use aoc_2020::nineteen06::{part_1_impl, part_2_impl};
+	let input = load(2019, 6);
+	c.bench_function("2019-06 Part 1", |b| b.iter(|| part_1_impl(black_box(&input))));
+	c.bench_function("2019-06 Part 2", |b| b.iter(|| part_2_impl(black_box(&input))));
 }
 
 pub fn nineteen07(c: &mut Criterion) {
-	use aoc_2020::nineteen07::{perform_work, DAY_7_DATA};
-	let data: Vec<i64> = Vec::from(DAY_7_DATA);
-	c.bench_function("2019-07 Part 1", |b| b.iter(|| perform_work(black_box(data.clone()), black_box(0..=4), black_box(false))));
-	c.bench_function("2019-07 Part 2", |b| b.iter(|| perform_work(black_box(data.clone()), black_box(5..=9), black_box(true))));
+	use aoc_2020::nineteen07::perform_work;
+	let data = memory_from_str(&load(2019, 7)).unwrap();
+	c.bench_function("2019-07 Part 1", |b| b.iter(|| perform_work(black_box(data.clone()), black_box(0..=4), black_box(false), black_box(true))));
+	c.bench_function("2019-07 Part 2", |b| b.iter(|| perform_work(black_box(data.clone()), black_box(5..=9), black_box(true), black_box(true))));
 }
 
 pub fn nineteen08(c: &mut Criterion) {
-	use aoc_2020::nineteen08::{part_1_impl, part_2_impl, DAY_8_DATA};
-	c.bench_function("2019-08 Part 1", |b| b.iter(|| part_1_impl(black_box(DAY_8_DATA), black_box(25), black_box(6))));
-	c.bench_function("2019-08 Part 2", |b| b.iter(|| part_2_impl(black_box(DAY_8_DATA), black_box(25), black_box(6))));
+	use aoc_2020::nineteen08::{part_1_impl, part_2_impl};
+	let input = load(2019, 8);
+	let input = input.trim();
+	c.bench_function("2019-08 Part 1", |b| b.iter(|| part_1_impl(black_box(input), black_box(25), black_box(6))));
+	c.bench_function("2019-08 Part 2", |b| b.iter(|| part_2_impl(black_box(input), black_box(25), black_box(6))));
 }
 
 pub fn nineteen09(c: &mut Criterion) {
-	use aoc_2020::nineteen09::{parts_impl, DAY_9_DATA};
-	c.bench_function("2019-09 Part 1", |b| b.iter(|| parts_impl(black_box(DAY_9_DATA), black_box(1))));
-	c.bench_function("2019-09 Part 2", |b| b.iter(|| parts_impl(black_box(DAY_9_DATA), black_box(9))));
+	use aoc_2020::nineteen09::parts_impl;
+	let memory = memory_from_str(&load(2019, 9)).unwrap();
+	c.bench_function("2019-09 Part 1", |b| b.iter(|| parts_impl(black_box(memory.clone()), black_box(1))));
+	c.bench_function("2019-09 Part 2", |b| b.iter(|| parts_impl(black_box(memory.clone()), black_box(2))));
 }
 
 pub fn nineteen10(c: &mut Criterion) {
-	use aoc_2020::nineteen10::{solve, FL_CONT};
-	c.bench_function("2019-10 Action function", |b| b.iter(|| solve(black_box(FL_CONT))));
+	use aoc_2020::nineteen10::solve;
+	let input = load(2019, 10);
+	c.bench_function("2019-10 Action function", |b| b.iter(|| solve(black_box(&input))));
 }
 
 pub fn nineteen11(c: &mut Criterion) {
-	use aoc_2020::nineteen11::{part_1_impl, part_2_impl, DAY_11_DATA};
-	c.bench_function("2019-11 Part 1", |b| b.iter(|| part_1_impl(black_box(DAY_11_DATA))));
-	c.bench_function("2019-11 Part 2", |b| b.iter(|| part_2_impl(black_box(DAY_11_DATA))));
+	use aoc_2020::nineteen11::{part_1_impl, part_2_impl};
+	let memory = memory_from_str(&load(2019, 11)).unwrap();
+	c.bench_function("2019-11 Part 1", |b| b.iter(|| part_1_impl(black_box(memory.clone()))));
+	c.bench_function("2019-11 Part 2", |b| b.iter(|| part_2_impl(black_box(memory.clone()))));
 }
 
 pub fn nineteen12(c: &mut Criterion) {
-	use aoc_2020::nineteen12::{part_1_impl, part_2_impl};
+	use aoc_2020::nineteen12::{part_1_impl, part_2_impl, parse_moons};
+	let (moon_a, moon_b, moon_c, moon_d) = parse_moons(&load(2019, 12)).unwrap();
 	c.bench_function("2019-12 Part 1", |b| b.iter(|| part_1_impl(
-		black_box((-17, 9, -5)),
-		black_box((-1, 7, 13)),
-		black_box((-19, 12, 5)),
-		black_box((-6, -6, -4)),
+		black_box(moon_a),
+		black_box(moon_b),
+		black_box(moon_c),
+		black_box(moon_d),
 	)));
 	c.bench_function("2019-12 Part 2", |b| b.iter(|| part_2_impl(
-		black_box((-17, 9, -5)),
-		black_box((-1, 7, 13)),
-		black_box((-19, 12, 5)),
-		black_box((-6, -6, -4)),
+		black_box(moon_a),
+		black_box(moon_b),
+		black_box(moon_c),
+		black_box(moon_d),
 	)));
 }
 
 pub fn nineteen13(c: &mut Criterion) {
-	use aoc_2020::nineteen13::{part_1_impl, part_2_impl, DAY_13_DATA};
-	c.bench_function("2019-13 Part 1", |b| b.iter(|| part_1_impl(black_box(Vec::from(DAY_13_DATA)))));
-	c.bench_function("2019-13 Part 2", |b| b.iter(|| part_2_impl(black_box(Vec::from(DAY_13_DATA)))));
+	use aoc_2020::nineteen13::{part_1_impl, part_2_impl};
+	let memory = memory_from_str(&load(2019, 13)).unwrap();
+	c.bench_function("2019-13 Part 1", |b| b.iter(|| part_1_impl(black_box(memory.clone()))));
+	c.bench_function("2019-13 Part 2", |b| b.iter(|| part_2_impl(black_box(memory.clone()))));
 }
 
 pub fn nineteen14(c: &mut Criterion) {
-	use aoc_2020::nineteen14::{part_1_impl, part_2_impl, DAY_14_DATA};
-	c.bench_function("2019-14 Part 1", |b| b.iter(|| part_1_impl(black_box(DAY_14_DATA))));
-	c.bench_function("2019-14 Part 2", |b| b.iter(|| part_2_impl(black_box(DAY_14_DATA))));
+	use aoc_2020::nineteen14::{part_1_impl, part_2_impl};
+	let input = load(2019, 14);
+	c.bench_function("2019-14 Part 1", |b| b.iter(|| part_1_impl(black_box(&input))));
+	c.bench_function("2019-14 Part 2", |b| b.iter(|| part_2_impl(black_box(&input))));
 }
 
 pub fn twenty22(c: &mut Criterion) {
 	use aoc_2020::day22::{Code};
 	let code: Code = Code {};
-	c.bench_function("2020-22 Part 1", |b| b.iter(|| code.part1()));
-	c.bench_function("2020-22 Part 2", |b| b.iter(|| code.part2()));
+	let input = code.parse(&load(2020, 22)).unwrap();
+	c.bench_function("2020-22 Part 1", |b| b.iter(|| code.part1(&input)));
+	c.bench_function("2020-22 Part 2", |b| b.iter(|| code.part2(&input)));
 }
 
 pub fn twenty23(c: &mut Criterion) {
 	use aoc_2020::day23::{Code};
 	let code: Code = Code {};
-	c.bench_function("2020-23 Part 1", |b| b.iter(|| code.part1()));
-	c.bench_function("2020-23 Part 2", |b| b.iter(|| code.part2()));
+	let input = code.parse(&load(2020, 23)).unwrap();
+	c.bench_function("2020-23 Part 1", |b| b.iter(|| code.part1(&input)));
+	c.bench_function("2020-23 Part 2", |b| b.iter(|| code.part2(&input)));
 }
 
 pub fn twenty24(c: &mut Criterion) {
 	use aoc_2020::day24::{Code};
 	let code: Code = Code {};
-	c.bench_function("2020-24 Part 1", |b| b.iter(|| code.part1()));
-	c.bench_function("2020-24 Part 2", |b| b.iter(|| code.part2()));
+	let input = code.parse(&load(2020, 24)).unwrap();
+	c.bench_function("2020-24 Part 1", |b| b.iter(|| code.part1(&input)));
+	c.bench_function("2020-24 Part 2", |b| b.iter(|| code.part2(&input)));
 }
 
 pub fn twenty25(c: &mut Criterion) {
 	use aoc_2020::day25::Code;
 	let code: Code = Code {};
-	c.bench_function("2020-25 Only Part", |b| b.iter(|| code.run()));
-}
-
-criterion_group!(benches,nineteen13, nineteen14, nineteen10, nineteen12 ,nineteen11, nineteen07,
+	let input = code.parse(&load(2020, 25)).unwrap();
+	c.bench_function("2020-25 Only Part", |b| b.iter(|| code.run(&input)));
+}
+
+fn run_with(engine: Engine, memory: &Memory, input: &[i64]) -> Option<i64> {
+	let mut vm = IntCodeVM::new(memory.clone());
+	vm.set_engine(engine);
+	for &value in input {
+		vm.push_input(value);
+	}
+	vm.run_all().unwrap();
+	vm.pop_output()
+}
+
+pub fn intcode_engines(c: &mut Criterion) {
+	let boost = memory_from_str(&load(2019, 9)).unwrap();
+	let gravity = memory_from_str(&load(2019, 2)).unwrap();
+	for &(name, engine) in &[("Interpreter", Engine::Interpreter), ("Cached", Engine::Cached)] {
+		// One long run, most of it in a few hot loops
+		c.bench_function(&format!("Intcode {} 2019-09 Part 2", name), |b| {
+			b.iter(|| run_with(engine, black_box(&boost), &[2]))
+		});
+		// Lots of short runs, each starting with an empty cache
+		c.bench_function(&format!("Intcode {} 2019-02 Every noun and verb", name), |b| {
+			b.iter(|| {
+				let mut found = 0;
+				for noun in 0..100 {
+					for verb in 0..100 {
+						let mut memory = gravity.clone();
+						memory[1] = noun;
+						memory[2] = verb;
+						let mut vm = IntCodeVM::new(memory);
+						vm.set_engine(engine);
+						vm.run_all().unwrap();
+						found += vm.get_zero() % 2;
+					}
+				}
+				found
+			})
+		});
+	}
+}
+
+criterion_group!(benches, intcode_engines,nineteen13, nineteen14, nineteen10, nineteen12 ,nineteen11, nineteen07,
 	 nineteen08, nineteen09, nineteen01, nineteen02, nineteen03, 
 	 nineteen04, nineteen05, nineteen06, twenty22, twenty23, 
 	 twenty24, twenty25);
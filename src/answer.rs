@@ -0,0 +1,123 @@
+use std::convert::TryInto;
+use std::fmt;
+
+use crate::image_layer::ImageLayer;
+
+/// What a solution hands back for one part of a day
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+	Int(i64),
+	Text(String),
+	/// Some days draw their answer, reading the letters off is left to whoever is looking
+	Image(ImageLayer),
+	Lines(Vec<String>),
+}
+
+impl Answer {
+	/// Whether the answer needs more than one line to show
+	pub fn is_multiline(&self) -> bool {
+		match self {
+			Answer::Int(_) => false,
+			Answer::Text(text) => text.contains('\n'),
+			Answer::Image(_) => true,
+			Answer::Lines(lines) => lines.len() > 1,
+		}
+	}
+}
+
+impl fmt::Display for Answer {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Answer::Int(value) => write!(f, "{}", value),
+			Answer::Text(text) => write!(f, "{}", text),
+			Answer::Image(image) => write!(f, "{}", image),
+			Answer::Lines(lines) => write!(f, "{}", lines.join("\n")),
+		}
+	}
+}
+
+macro_rules! int_answer {
+	($($t:ty),*) => {
+		$(
+			impl From<$t> for Answer {
+				fn from(value: $t) -> Self {
+					// Anything too big for an i64 is kept exactly, just as text
+					match value.try_into() {
+						Ok(value) => Answer::Int(value),
+						Err(_) => Answer::Text(value.to_string()),
+					}
+				}
+			}
+		)*
+	};
+}
+
+int_answer!(i32, i64, u8, u32, u64, usize);
+
+impl From<String> for Answer {
+	fn from(value: String) -> Self {
+		Answer::Text(value)
+	}
+}
+
+impl From<&str> for Answer {
+	fn from(value: &str) -> Self {
+		Answer::Text(value.to_string())
+	}
+}
+
+impl From<ImageLayer> for Answer {
+	fn from(value: ImageLayer) -> Self {
+		Answer::Image(value)
+	}
+}
+
+impl From<Vec<String>> for Answer {
+	fn from(value: Vec<String>) -> Self {
+		Answer::Lines(value)
+	}
+}
+
+/// The answers to a day, a part is missing if it wasn't asked for or the day doesn't have one
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+	pub part1: Option<Answer>,
+	pub part2: Option<Answer>,
+}
+
+impl Answers {
+	pub fn new(part1: Option<Answer>, part2: Option<Answer>) -> Answers {
+		Answers { part1, part2 }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::image_layer::Pixel;
+
+	#[test]
+	fn test_from_numbers() {
+		assert_eq!(Answer::from(42_usize), Answer::Int(42));
+		assert_eq!(Answer::from(-3_i64), Answer::Int(-3));
+		assert_eq!(Answer::from("abc"), Answer::Text("abc".to_string()));
+		assert_eq!(Answer::from(u64::MAX), Answer::Text(u64::MAX.to_string()));
+		// Right at the edge of what fits
+		assert_eq!(Answer::from(i64::MAX as u64), Answer::Int(i64::MAX));
+		assert_eq!(Answer::from(i64::MAX as usize + 1), Answer::Text("9223372036854775808".to_string()));
+	}
+
+	#[test]
+	fn test_display() {
+		assert_eq!(Answer::Int(1686).to_string(), "1686");
+		assert!(!Answer::Int(1686).is_multiline());
+		let lines = Answer::Lines(vec!["a".to_string(), "b".to_string()]);
+		assert_eq!(lines.to_string(), "a\nb");
+		assert!(lines.is_multiline());
+		let image = Answer::Image(ImageLayer {
+			pixels: vec![vec![Pixel::White, Pixel::Black], vec![Pixel::Black, Pixel::White]],
+		});
+		assert_eq!(image.to_string().lines().count(), 2);
+		assert!(image.is_multiline());
+	}
+}
@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use crate::input::INPUT_DIR;
+use crate::registry::PartResults;
+use crate::{Answer, Part};
+
+/// Where the accepted answers for a year are kept: `inputs/<year>/answers.toml`
+pub fn default_path(year: u32) -> PathBuf {
+	Path::new(INPUT_DIR).join(year.to_string()).join("answers.toml")
+}
+
+/// The answers the site accepted for one year, keyed by day.
+///
+/// The file has a table per day, numbers are written as numbers and anything else
+/// (drawn letters, lines of output) as a string:
+///
+/// ```toml
+/// [1]
+/// part1 = 3303995
+/// part2 = 4953118
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnswerFile {
+	days: HashMap<u32, (Option<Answer>, Option<Answer>)>,
+}
+
+impl AnswerFile {
+	/// Load the answers for a year. A year that doesn't have a file yet just has no answers
+	pub fn load(year: u32) -> Result<AnswerFile> {
+		let path = default_path(year);
+		if !path.is_file() {
+			return Ok(AnswerFile::default());
+		}
+		let text = fs::read_to_string(&path).wrap_err_with(|| format!("Couldn't read {}", path.display()))?;
+		AnswerFile::parse(&text).wrap_err_with(|| format!("{} is not a valid answers file", path.display()))
+	}
+
+	pub fn parse(text: &str) -> Result<AnswerFile> {
+		let table: toml::value::Table = toml::from_str(text)?;
+		let mut days = HashMap::new();
+		for (key, value) in table {
+			let day: u32 = key.parse().wrap_err_with(|| format!("{:?} is not a day", key))?;
+			let parts = value.as_table().ok_or_else(|| eyre!("Day {} should be a table of parts", day))?;
+			if let Some(unknown) = parts.keys().find(|k| *k != "part1" && *k != "part2") {
+				return Err(eyre!("Day {} has an unknown part {:?}", day, unknown));
+			}
+			let part = |name: &str| -> Result<Option<Answer>> {
+				match parts.get(name) {
+					None => Ok(None),
+					Some(toml::Value::Integer(value)) => Ok(Some(Answer::Int(*value))),
+					Some(toml::Value::String(value)) => Ok(Some(Answer::Text(value.clone()))),
+					Some(other) => Err(eyre!("Day {} {} should be a number or a string, not {}", day, name, other.type_str())),
+				}
+			};
+			days.insert(day, (part("part1")?, part("part2")?));
+		}
+		Ok(AnswerFile { days })
+	}
+
+	/// The recorded answer for one part of a day, if there is one
+	pub fn get(&self, day: u32, part: Part) -> Option<&Answer> {
+		let (part1, part2) = self.days.get(&day)?;
+		match part {
+			Part::Part1 => part1.as_ref(),
+			Part::Part2 => part2.as_ref(),
+			Part::Both => None,
+		}
+	}
+
+	/// Compare what a day produced against what was recorded for it
+	pub fn verify(&self, day: u32, results: &PartResults) -> (Verdict, Verdict) {
+		(
+			Verdict::new(self.get(day, Part::Part1), &results.part1),
+			Verdict::new(self.get(day, Part::Part2), &results.part2),
+		)
+	}
+}
+
+/// Write down an answer the site accepted, so `verify` checks it from now on
+pub fn record(year: u32, day: u32, part: Part, answer: &Answer) -> Result<()> {
+	let path = default_path(year);
+	let text = if path.is_file() {
+		fs::read_to_string(&path).wrap_err_with(|| format!("Couldn't read {}", path.display()))?
+	} else {
+		String::new()
+	};
+	let text = add_answer(&text, day, part, answer)?;
+	if let Some(dir) = path.parent() {
+		fs::create_dir_all(dir).wrap_err_with(|| format!("Couldn't create {}", dir.display()))?;
+	}
+	fs::write(&path, text).wrap_err_with(|| format!("Couldn't write {}", path.display()))
+}
+
+/// Add a part to the text of an answers file, keeping the days in order and everything else as it was
+fn add_answer(text: &str, day: u32, part: Part, answer: &Answer) -> Result<String> {
+	let name = match part {
+		Part::Part1 => "part1",
+		Part::Part2 => "part2",
+		Part::Both => return Err(eyre!("Answers are recorded one part at a time")),
+	};
+	if AnswerFile::parse(text)?.get(day, part).is_some() {
+		return Err(eyre!("Day {} {} already has an answer", day, name));
+	}
+	let value = match answer {
+		Answer::Int(value) => value.to_string(),
+		other => toml::Value::String(normalise(other)).to_string(),
+	};
+	let entry = format!("{} = {}", name, value);
+
+	let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+	let header = |line: &String| -> Option<u32> {
+		line.trim().strip_prefix('[')?.strip_suffix(']')?.trim().trim_matches('"').parse().ok()
+	};
+	match lines.iter().position(|line| header(line) == Some(day)) {
+		Some(start) => {
+			// After the last line of the day's table that isn't blank
+			let end = lines[start + 1..].iter().position(|line| header(line).is_some()).map_or(lines.len(), |i| start + 1 + i);
+			let at = (start + 1..end).rev().find(|&i| !lines[i].trim().is_empty()).map_or(start + 1, |i| i + 1);
+			lines.insert(at, entry);
+		}
+		None => {
+			let section = vec![format!("[{}]", day), entry, String::new()];
+			match lines.iter().position(|line| header(line).is_some_and(|other| other > day)) {
+				Some(at) => {
+					lines.splice(at..at, section);
+				}
+				None => {
+					if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+						lines.push(String::new());
+					}
+					lines.extend(section);
+				}
+			}
+		}
+	}
+	let mut text = lines.join("\n");
+	text.truncate(text.trim_end().len());
+	text.push('\n');
+	AnswerFile::parse(&text).wrap_err("Recording the answer would break the answers file")?;
+	Ok(text)
+}
+
+/// How one part of a day compared against its recorded answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+	Pass,
+	Fail { expected: String, actual: String },
+	/// Nothing has been recorded for this part, so there's nothing to check against
+	Missing,
+	Error(String),
+}
+
+impl Verdict {
+	pub fn new(expected: Option<&Answer>, actual: &Result<Option<Answer>>) -> Verdict {
+		let expected = match expected {
+			Some(expected) => normalise(expected),
+			None => return Verdict::Missing,
+		};
+		match actual {
+			Err(e) => Verdict::Error(e.to_string()),
+			Ok(None) => Verdict::Fail { expected, actual: "N/A".to_string() },
+			Ok(Some(actual)) => {
+				let actual = normalise(actual);
+				if actual == expected {
+					Verdict::Pass
+				} else {
+					Verdict::Fail { expected, actual }
+				}
+			}
+		}
+	}
+
+	pub fn is_failure(&self) -> bool {
+		matches!(self, Verdict::Fail { .. } | Verdict::Error(_))
+	}
+}
+
+impl fmt::Display for Verdict {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Verdict::Pass => write!(f, "pass"),
+			Verdict::Fail { expected, actual } if expected.contains('\n') || actual.contains('\n') => {
+				write!(f, "fail, expected:\n{}\ngot:\n{}", expected, actual)
+			}
+			Verdict::Fail { expected, actual } => write!(f, "fail, expected {} got {}", expected, actual),
+			Verdict::Missing => write!(f, "missing"),
+			Verdict::Error(e) => write!(f, "error: {}", e),
+		}
+	}
+}
+
+/// Answers are compared as they'd be printed. Drawn answers pick up trailing spaces
+/// and blank lines that nobody would copy into the answers file, so those are ignored
+fn normalise(answer: &Answer) -> String {
+	let text = answer.to_string();
+	let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+	lines.join("\n").trim_matches('\n').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::registry;
+
+	#[test]
+	fn test_parse() {
+		let file = AnswerFile::parse("[1]\npart1 = 12\npart2 = \"abc\"\n\n[3]\npart2 = 4\n").unwrap();
+		assert_eq!(file.get(1, Part::Part1), Some(&Answer::Int(12)));
+		assert_eq!(file.get(1, Part::Part2), Some(&Answer::Text("abc".to_string())));
+		assert_eq!(file.get(3, Part::Part1), None);
+		assert_eq!(file.get(2, Part::Part1), None);
+		assert!(AnswerFile::parse("[one]\npart1 = 1\n").is_err());
+		assert!(AnswerFile::parse("[1]\npart3 = 1\n").is_err());
+		assert!(AnswerFile::parse("[1]\npart1 = 1.5\n").is_err());
+	}
+
+	#[test]
+	fn test_add_answer() {
+		let text = "# Kept\n[1]\npart1 = 12\n\n[3]\npart2 = 4\n";
+		let text = add_answer(text, 1, Part::Part2, &Answer::Text("abc".to_string())).unwrap();
+		assert_eq!(text, "# Kept\n[1]\npart1 = 12\npart2 = \"abc\"\n\n[3]\npart2 = 4\n");
+		let text = add_answer(&text, 2, Part::Part1, &Answer::Int(-5)).unwrap();
+		assert_eq!(text, "# Kept\n[1]\npart1 = 12\npart2 = \"abc\"\n\n[2]\npart1 = -5\n\n[3]\npart2 = 4\n");
+		let text = add_answer(&text, 4, Part::Part1, &Answer::Int(7)).unwrap();
+		assert!(text.ends_with("[3]\npart2 = 4\n\n[4]\npart1 = 7\n"));
+		assert_eq!(add_answer("", 1, Part::Part1, &Answer::Int(1)).unwrap(), "[1]\npart1 = 1\n");
+		assert!(add_answer(&text, 1, Part::Part1, &Answer::Int(13)).is_err());
+		let file = AnswerFile::parse(&text).unwrap();
+		assert_eq!(file.get(2, Part::Part1), Some(&Answer::Int(-5)));
+		assert_eq!(file.get(1, Part::Part2), Some(&Answer::Text("abc".to_string())));
+	}
+
+	#[test]
+	fn test_verdict() {
+		let expected = Answer::Int(1686);
+		assert_eq!(Verdict::new(Some(&expected), &Ok(Some(Answer::from(1686_usize)))), Verdict::Pass);
+		assert_eq!(Verdict::new(Some(&Answer::Text("1686".to_string())), &Ok(Some(Answer::Int(1686)))), Verdict::Pass);
+		assert_eq!(
+			Verdict::new(Some(&expected), &Ok(Some(Answer::Int(1145)))),
+			Verdict::Fail { expected: "1686".to_string(), actual: "1145".to_string() }
+		);
+		assert_eq!(Verdict::new(None, &Ok(Some(Answer::Int(1145)))), Verdict::Missing);
+		assert!(Verdict::new(Some(&expected), &Ok(None)).is_failure());
+		assert!(Verdict::new(Some(&expected), &Err(eyre!("oops"))).is_failure());
+	}
+
+	#[test]
+	fn test_drawn_answers_ignore_trailing_space() {
+		let drawn = Answer::Lines(vec!["█  █ ".to_string(), "████ ".to_string(), String::new()]);
+		let recorded = Answer::Text("\n█  █\n████\n".to_string());
+		assert_eq!(Verdict::new(Some(&recorded), &Ok(Some(drawn))), Verdict::Pass);
+	}
+
+	#[test]
+	fn test_answer_files_are_valid() {
+		for year in registry::years() {
+			let file = AnswerFile::load(year).unwrap();
+			for &day in file.days.keys() {
+				assert!(registry::find(year, day).is_some(), "{} day {} has an answer but no solution", year, day);
+			}
+		}
+	}
+
+	/// Every day against its recorded answers, this takes a while so run it with
+	/// `cargo test --release -- --ignored`
+	#[test]
+	#[ignore]
+	fn test_verify_all() {
+		let mut failures = Vec::new();
+		for year in registry::years() {
+			let file = AnswerFile::load(year).unwrap();
+			for solution in registry::for_year(year) {
+				let input = crate::input::load(Some(&crate::input::default_path(year, solution.day)), year, solution.day).unwrap();
+				let parsed = solution.solver.parse(&input).unwrap();
+				let (part1, part2) = file.verify(solution.day, &solution.run_parts(parsed.as_ref()));
+				for (part, verdict) in [(1, part1), (2, part2)] {
+					if verdict.is_failure() {
+						failures.push(format!("{} day {} part {}: {}", year, solution.day, part, verdict));
+					}
+				}
+			}
+		}
+		assert!(failures.is_empty(), "{}", failures.join("\n"));
+	}
+}
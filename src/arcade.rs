@@ -0,0 +1,86 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use color_eyre::eyre::{Result, WrapErr};
+use structopt::StructOpt;
+
+use aoc_2020::input;
+use aoc_2020::intcode::arcade::{self, Ending, FollowBall, FrameLog, Keyboard, Policy, Script, Terminal, Tile};
+use aoc_2020::intcode::memory_from_str;
+
+use crate::day::DayArgs;
+
+#[derive(StructOpt)]
+pub struct Arcade {
+    #[structopt(flatten)]
+    day: DayArgs,
+    /// Where to read the game from. Defaults to the day's input
+    #[structopt(long, short, parse(from_os_str))]
+    input: Option<PathBuf>,
+    /// Play it yourself: type moves and press enter, `<` left, `>` right, `.` (or nothing) stays put
+    #[structopt(long, conflicts_with = "script")]
+    play: bool,
+    /// Play the moves in a file, written the same way
+    #[structopt(long, parse(from_os_str))]
+    script: Option<PathBuf>,
+    /// Write the moves made to a file, for --script
+    #[structopt(long, parse(from_os_str))]
+    record: Option<PathBuf>,
+    /// Show each frame in the terminal as it's played
+    #[structopt(long)]
+    live: bool,
+    /// How long to show each frame for with --live, in milliseconds
+    #[structopt(long, default_value = "20")]
+    delay: u64,
+    /// Write every frame to a file
+    #[structopt(long, parse(from_os_str))]
+    frames: Option<PathBuf>,
+}
+
+impl Arcade {
+    pub fn run(&self) -> Result<String> {
+        let day = self.day.day()?;
+        let source = input::load(self.input.as_deref(), day.year(), day.day())?;
+        let memory = memory_from_str(&source).wrap_err("That isn't an intcode program")?;
+
+        let mut policy: Box<dyn Policy> = if self.play {
+            Box::new(Keyboard::new(io::stdin().lock()))
+        } else if let Some(path) = &self.script {
+            let script = fs::read_to_string(path).wrap_err_with(|| format!("Couldn't read {}", path.display()))?;
+            Box::new(Script::parse(&script))
+        } else {
+            Box::new(FollowBall)
+        };
+        // Someone playing has to see what they're doing
+        let terminal = if self.live || self.play {
+            Some(Terminal { delay: if self.play { Duration::default() } else { Duration::from_millis(self.delay) } })
+        } else {
+            None
+        };
+        let log = match &self.frames {
+            Some(path) => Some(FrameLog::create(path).wrap_err_with(|| format!("Couldn't create {}", path.display()))?),
+            None => None,
+        };
+
+        let mut cabinet = arcade::Arcade::free_play(memory);
+        let mut renderer = (terminal, log);
+        let ending = cabinet.play(&mut policy, &mut renderer).wrap_err("The game failed")?;
+        if let (Some(log), Some(path)) = (renderer.1, &self.frames) {
+            log.finish().wrap_err_with(|| format!("Couldn't write the frames to {}", path.display()))?;
+        }
+        if let Some(path) = &self.record {
+            fs::write(path, Script::write(cabinet.moves()) + "\n")
+                .wrap_err_with(|| format!("Couldn't write the moves to {}", path.display()))?;
+        }
+
+        let screen = cabinet.screen();
+        let ending = match ending {
+            Ending::GameOver if screen.count(Tile::Block) == 0 => "Every block's gone".to_string(),
+            Ending::GameOver => format!("Game over with {} block(s) left", screen.count(Tile::Block)),
+            Ending::OutOfMoves => "Out of moves".to_string(),
+        };
+        Ok(format!("{}{} after {} move(s)", screen.render(), ending, cabinet.moves().len()))
+    }
+}
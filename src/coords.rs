@@ -37,9 +37,9 @@ impl Coords {
 		}else if (self.x - 1) == next.x {
 			Direction::West
 		} else if (self.y + 1) == next.y {
-			Direction::North
-		} else {
 			Direction::South
+		} else {
+			Direction::North
 		}
 	}
 	pub fn flat_dist(&self, oth: &Coords) -> usize{
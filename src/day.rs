@@ -1,57 +1,143 @@
-use std::convert::TryInto;
-use std::str::FromStr;
+use std::env;
 
-use aoc_2020::*;
-use either::*;
+use aoc_2020::registry::{self, Solution};
+use color_eyre::eyre::{eyre, Result, WrapErr};
 use structopt::StructOpt;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, StructOpt)]
+/// Where the default year comes from when a day is given on its own
+pub const YEAR_VAR: &str = "AOC_YEAR";
+
+/// The first year there was an Advent of Code
+const FIRST_YEAR: u32 = 2015;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct Day {
+	year: u32,
 	day: u32,
 }
 
-impl FromStr for Day {
-	type Err = &'static str;
+/// How a day is picked on the command line, flatten this into a subcommand that works on a single day
+#[derive(Debug, StructOpt)]
+pub struct DayArgs {
+	/// The day, as `2019 13`, `2019-13`, `2019/13` or `201913`. Just `13` uses the default year
+	#[structopt(required = true, min_values = 1, max_values = 2)]
+	day: Vec<String>,
+	/// The year, if the day doesn't say. Defaults to $AOC_YEAR, then the latest year with solutions
+	#[structopt(long, short)]
+	year: Option<u32>,
+}
 
-	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		let day: usize = s.parse().map_err(|_| "Day needs to be an integer")?;
-		let day = day.try_into().unwrap();
-		Ok(Self { day })
+impl DayArgs {
+	pub fn day(&self) -> Result<Day> {
+		Day::parse(&self.day.join(" "), self.year)
 	}
 }
 
 impl Day {
-	pub fn get_code(&self) -> Either<&dyn AoCDay, &dyn SinglePart> {
-		match self.day {
-			// 201901 => Left(&nineteen01::Code),
-			// 201902 => Left(&nineteen02::Code),
-			// 201903 => Right(&nineteen03::Code),
-			// 201904 => Right(&nineteen04::Code),
-			// 201905 => Left(&nineteen05::Code),
-			// 201906 => Right(&nineteen06::Code),
-			// 201907 => Left(&nineteen07::Code),
-			// 201908 => Left(&nineteen08::Code),
-			// 201909 => Left(&nineteen09::Code),
-			// 201910 => Right(&nineteen10::Code),
-			// 201911 => Left(&nineteen11::Code),
-			// 201912 => Left(&nineteen12::Code),
-			// 201913 => Left(&nineteen13::Code),
-			// 201914 => Left(&nineteen14::Code),
-			// 201915 => Right(&nineteen15::Code),
-			// 201916 => Left(&nineteen16::Code),
-			// 201917 => Right(&nineteen17::Code),
-			// 201918 => Left(&nineteen18::Code),
-			//201919 => Left(&nineteen19::Code),
-			//201920 => Left(&nineteen20::Code),
-			//201921 => Left(&nineteen21::Code),
-			//201922 => Left(&nineteen22::Code),
-			//201923 => Left(&nineteen23::Code),
-			201925 => Left(&nineteen25::Code),
-			// 202022 => Left(&day22::Code),
-			// 202023 => Left(&day23::Code),
-			// 202024 => Left(&day24::Code),
-			// 202025 => Right(&day25::Code),
-			_ => unreachable!(),
+	pub fn new(year: u32, day: u32) -> Result<Day> {
+		if year < FIRST_YEAR {
+			return Err(eyre!("{} is too early, Advent of Code started in {}", year, FIRST_YEAR));
+		}
+		if !(1..=25).contains(&day) {
+			return Err(eyre!("Day {} doesn't exist, days go from 1 to 25", day));
+		}
+		Ok(Day { year, day })
+	}
+
+	/// Read a day written any of the ways `DayArgs` allows. The year is needed when the text doesn't have one,
+	/// and has to agree with it when it does
+	pub fn parse(s: &str, year: Option<u32>) -> Result<Day> {
+		let parts: Vec<&str> = s
+			.split(|c: char| c == '-' || c == '/' || c.is_whitespace())
+			.filter(|p| !p.is_empty())
+			.collect();
+		let number = |part: &str| -> Result<u32> {
+			part.parse().wrap_err_with(|| format!("{:?} in {:?} isn't a number", part, s))
+		};
+		let (given_year, day) = match parts.as_slice() {
+			[year, day] => (Some(number(year)?), number(day)?),
+			// The old way of writing both at once, 201913 is 2019 day 13
+			[both] if both.len() > 2 => {
+				let both = number(both)?;
+				(Some(both / 100), both % 100)
+			}
+			[day] => (None, number(day)?),
+			_ => return Err(eyre!("{:?} isn't a day, try something like `2019 13`", s)),
+		};
+		let year = match (given_year, year) {
+			(Some(given), Some(year)) if given != year => {
+				return Err(eyre!("{:?} is in {} but --year says {}", s, given, year));
+			}
+			(Some(year), _) | (None, Some(year)) => year,
+			(None, None) => default_year()?,
+		};
+		Day::new(year, day)
+	}
+
+	pub fn year(&self) -> u32 {
+		self.year
+	}
+
+	pub fn day(&self) -> u32 {
+		self.day
+	}
+
+	pub fn get_code(&self) -> Result<&'static Solution> {
+		registry::find(self.year(), self.day()).ok_or_else(|| {
+			let available = registry::for_year(self.year())
+				.iter()
+				.map(|s| s.day.to_string())
+				.collect::<Vec<_>>();
+			if available.is_empty() {
+				eyre!("There are no solutions for {}", self.year())
+			} else {
+				eyre!(
+					"There is no solution for {} day {}, the days available are: {}",
+					self.year(),
+					self.day(),
+					available.join(", ")
+				)
+			}
+		})
+	}
+}
+
+/// Used when the year isn't on the command line. $AOC_YEAR is only a default, so unlike --year
+/// it doesn't have to agree with a day that has its own year
+fn default_year() -> Result<u32> {
+	if let Ok(year) = env::var(YEAR_VAR) {
+		return year.trim().parse().wrap_err_with(|| format!("${} is {:?}, which isn't a year", YEAR_VAR, year));
+	}
+	registry::years()
+		.last()
+		.copied()
+		.ok_or_else(|| eyre!("No year given and there are no solutions to pick one from"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse() {
+		let expected = Day::new(2019, 13).unwrap();
+		for s in &["2019 13", "2019-13", "2019/13", "201913", " 2019  13 "] {
+			assert_eq!(Day::parse(s, None).unwrap(), expected, "{}", s);
 		}
+		assert_eq!(Day::parse("13", Some(2019)).unwrap(), expected);
+		assert_eq!(Day::parse("2019 13", Some(2019)).unwrap(), expected);
+		assert_eq!(Day::parse("7", None).unwrap().day(), 7);
+	}
+
+	#[test]
+	fn test_parse_errors() {
+		assert!(Day::parse("2019 26", None).is_err());
+		assert!(Day::parse("2019 0", None).is_err());
+		assert!(Day::parse("2014 1", None).is_err());
+		assert!(Day::parse("2019 13", Some(2020)).is_err());
+		assert!(Day::parse("2019 thirteen", None).is_err());
+		assert!(Day::parse("2019 12 13", None).is_err());
+		// Used to panic
+		assert!(Day::parse("99999999999999999999", None).is_err());
 	}
 }
@@ -0,0 +1,69 @@
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+use color_eyre::eyre::{Result, WrapErr};
+use structopt::StructOpt;
+
+use aoc_2020::input;
+use aoc_2020::intcode::debug::{Debugger, Reply};
+use aoc_2020::intcode::memory_from_str;
+use aoc_2020::intcode::snapshot::Snapshot;
+
+use crate::day::DayArgs;
+
+const PROMPT: &str = "(intcode) ";
+
+#[derive(StructOpt)]
+pub struct Debug {
+    #[structopt(flatten)]
+    day: DayArgs,
+    /// Where to read the program from. Defaults to the day's input
+    #[structopt(long, short, parse(from_os_str))]
+    input: Option<PathBuf>,
+    /// Carry on from a file the debugger's `save` wrote instead of starting the program afresh
+    #[structopt(long, parse(from_os_str), conflicts_with = "input")]
+    resume: Option<PathBuf>,
+}
+
+impl Debug {
+    pub fn run(&self) -> Result<String> {
+        let day = self.day.day()?;
+        let mut debugger = match &self.resume {
+            Some(path) => {
+                let snapshot = Snapshot::load(path).wrap_err_with(|| format!("Couldn't resume from {}", path.display()))?;
+                println!("Resumed from {}, `help` lists the commands", path.display());
+                Debugger::resume(&snapshot)
+            }
+            None => {
+                let source = input::load(self.input.as_deref(), day.year(), day.day())?;
+                let memory = memory_from_str(&source).wrap_err("That isn't an intcode program")?;
+                println!("Loaded {} day {}, `help` lists the commands", day.year(), day.day());
+                Debugger::new(memory)
+            }
+        };
+
+        let stdin = io::stdin();
+        let mut lines = stdin.lock().lines();
+        // An empty line does the last command again, handy for stepping
+        let mut last = String::new();
+        loop {
+            print!("{}", PROMPT);
+            io::stdout().flush()?;
+            let line = match lines.next() {
+                Some(line) => line?,
+                None => break,
+            };
+            if !line.trim().is_empty() {
+                last = line;
+            }
+            match debugger.command(&last) {
+                Ok(Reply::Show(text)) if text.is_empty() => {}
+                Ok(Reply::Show(text)) => println!("{}", text),
+                Ok(Reply::Quit) => break,
+                Err(e) => eprintln!("{}", e),
+            }
+        }
+        let vm = debugger.vm();
+        Ok(format!("Left at ip {}, {:?}", vm.register, vm.state))
+    }
+}
@@ -75,12 +75,28 @@ impl Direction {
 			}
 		}
 	}
+	pub fn turn_left(self) -> Direction {
+		match self {
+			Direction::North => Direction::West,
+			Direction::East => Direction::North,
+			Direction::South => Direction::East,
+			Direction::West => Direction::South,
+		}
+	}
+	pub fn turn_right(self) -> Direction {
+		match self {
+			Direction::North => Direction::East,
+			Direction::East => Direction::South,
+			Direction::South => Direction::West,
+			Direction::West => Direction::North,
+		}
+	}
 	pub fn opposite(&self) -> Direction {
 		match self {
 			Direction::North => Direction::South,
-			Direction::East => Direction::East,
+			Direction::East => Direction::West,
 			Direction::South => Direction::North,
-			Direction::West => Direction::West,
+			Direction::West => Direction::East,
 		}
 	}
 }
@@ -0,0 +1,27 @@
+use std::path::PathBuf;
+
+use color_eyre::eyre::{Result, WrapErr};
+use structopt::StructOpt;
+
+use aoc_2020::input;
+use aoc_2020::intcode::{disasm, memory_from_str};
+
+use crate::day::DayArgs;
+
+#[derive(StructOpt)]
+pub struct Disasm {
+    #[structopt(flatten)]
+    day: DayArgs,
+    /// Where to read the program from, `-` for stdin. Defaults to the day's input
+    #[structopt(long, short, parse(from_os_str))]
+    input: Option<PathBuf>,
+}
+
+impl Disasm {
+    pub fn run(&self) -> Result<String> {
+        let day = self.day.day()?;
+        let source = input::load(self.input.as_deref(), day.year(), day.day())?;
+        let memory = memory_from_str(&source).wrap_err("That isn't an intcode program")?;
+        Ok(disasm::listing(&memory))
+    }
+}
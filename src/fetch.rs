@@ -0,0 +1,27 @@
+use color_eyre::eyre::Result;
+use structopt::StructOpt;
+
+use aoc_2020::input;
+use aoc_2020::site::Site;
+
+use crate::day::DayArgs;
+
+#[derive(StructOpt)]
+pub struct Fetch {
+    #[structopt(flatten)]
+    day: DayArgs,
+}
+
+impl Fetch {
+    pub fn run(&self) -> Result<String> {
+        let day = self.day.day()?;
+        let site = Site::from_env()?;
+        let cached = input::is_non_empty_file(&site.cached_path(day.year(), day.day()));
+        let path = site.fetch(day.year(), day.day())?;
+        Ok(if cached {
+            format!("Already have {}", path.display())
+        } else {
+            format!("Saved to {}", path.display())
+        })
+    }
+}
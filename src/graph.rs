@@ -364,9 +364,8 @@ impl Graph {
 									continue 'graph_ctile;
 								}
 							}
-							GraphType::Start(_) => {
-								continue 'graph_ctile;
-							}
+							// Somewhere a robot started is just floor once it's left
+							GraphType::Start(_) => {}
 							GraphType::Warp(_, _, down, num) => {
 								match current_tile {
 									GraphType::Warp(_, _, lmao, num) if lmao != down => {}
@@ -394,9 +393,6 @@ impl Graph {
 						}
 					}
 				}
-				if possible_keys.len() == 0 {
-					println!("No possible solutions for {:?}", current);
-				}
 				let real_keys: Vec<(GraphType, usize)> = possible_keys.into_iter()
 					.map(|node| (node, seen_weights[&node]))
 					.collect();
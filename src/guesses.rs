@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use crate::input::INPUT_DIR;
+use crate::site::Outcome;
+use crate::Part;
+
+/// Where the answers sent to the site for a year are remembered: `inputs/<year>/guesses.toml`
+pub fn default_path(year: u32) -> PathBuf {
+	Path::new(INPUT_DIR).join(year.to_string()).join("guesses.toml")
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Guess {
+	pub answer: String,
+	pub outcome: Outcome,
+}
+
+/// Every answer the site has judged, so the same wrong answer never gets sent twice.
+/// Answers that weren't looked at (sent too soon) aren't kept, but how long the site said to wait is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct History {
+	path: PathBuf,
+	guesses: BTreeMap<(u32, u8), Vec<Guess>>,
+	/// Nothing gets sent before this, in seconds since the epoch. Saved as `wait_until` at the top of the file
+	wait_until: Option<u64>,
+}
+
+impl History {
+	pub fn load(year: u32) -> Result<History> {
+		History::load_from(&default_path(year))
+	}
+
+	/// A missing file is just an empty history
+	pub fn load_from(path: &Path) -> Result<History> {
+		let mut history = History { path: path.to_path_buf(), guesses: BTreeMap::new(), wait_until: None };
+		if !path.is_file() {
+			return Ok(history);
+		}
+		let text = fs::read_to_string(path).wrap_err_with(|| format!("Couldn't read {}", path.display()))?;
+		history.parse(&text).wrap_err_with(|| format!("{} is not a valid guess history", path.display()))?;
+		Ok(history)
+	}
+
+	fn parse(&mut self, text: &str) -> Result<()> {
+		let table: toml::value::Table = toml::from_str(text)?;
+		for (day, parts) in table {
+			if day == WAIT_KEY {
+				let until = parts.as_integer().ok_or_else(|| eyre!("{} should be a number of seconds", WAIT_KEY))?;
+				self.wait_until = Some(until as u64);
+				continue;
+			}
+			let day: u32 = day.parse().wrap_err_with(|| format!("{:?} is not a day", day))?;
+			let parts = parts.as_table().ok_or_else(|| eyre!("Day {} should be a table of parts", day))?;
+			for (part, guesses) in parts {
+				let part = part_number(part.parse().map_err(|_| eyre!("Day {} has an unknown part {:?}", day, part))?)?;
+				let guesses = guesses.as_array().ok_or_else(|| eyre!("Day {} part {} should be a list", day, part))?;
+				for guess in guesses {
+					let field = |name: &str| {
+						guess
+							.get(name)
+							.and_then(|v| v.as_str())
+							.ok_or_else(|| eyre!("A guess for day {} part {} is missing its {}", day, part, name))
+					};
+					let outcome = match field("outcome")? {
+						"correct" => Outcome::Correct,
+						"too high" => Outcome::TooHigh,
+						"too low" => Outcome::TooLow,
+						"wrong" => Outcome::Wrong,
+						other => return Err(eyre!("{:?} isn't an outcome", other)),
+					};
+					let answer = field("answer")?.to_string();
+					self.guesses.entry((day, part)).or_default().push(Guess { answer, outcome });
+				}
+			}
+		}
+		Ok(())
+	}
+
+	pub fn guesses(&self, day: u32, part: Part) -> &[Guess] {
+		part_number(part)
+			.ok()
+			.and_then(|part| self.guesses.get(&(day, part)))
+			.map_or(&[], |guesses| guesses.as_slice())
+	}
+
+	/// Whether it's worth sending this answer. It isn't if the part has been solved, if it's been
+	/// tried already, or if it's on the wrong side of an earlier "too high" or "too low"
+	pub fn check(&self, day: u32, part: Part, answer: &str) -> Result<()> {
+		if let Some(until) = self.wait_until {
+			let now = unix_now();
+			if now < until {
+				return Err(eyre!("The site said to wait before answering again, there's {}s to go", until - now));
+			}
+		}
+		let guesses = self.guesses(day, part);
+		if let Some(correct) = guesses.iter().find(|g| g.outcome == Outcome::Correct) {
+			return Err(eyre!("Day {} {} has already been solved, the answer was {}", day, part, correct.answer));
+		}
+		if let Some(tried) = guesses.iter().find(|g| g.answer == answer) {
+			return Err(eyre!("{} has already been tried for day {} {}, it was {}", answer, day, part, tried.outcome));
+		}
+		if let Ok(value) = answer.parse::<i64>() {
+			for guess in guesses {
+				let bound = match guess.answer.parse::<i64>() {
+					Ok(bound) => bound,
+					Err(_) => continue,
+				};
+				let ruled_out = match guess.outcome {
+					Outcome::TooHigh => value >= bound,
+					Outcome::TooLow => value <= bound,
+					_ => false,
+				};
+				if ruled_out {
+					return Err(eyre!("{} can't be right, {} was already {}", answer, guess.answer, guess.outcome));
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Remember what the site said, if it actually judged the answer, or how long to wait if it didn't
+	pub fn record(&mut self, day: u32, part: Part, answer: &str, outcome: &Outcome) -> Result<()> {
+		if let Some(wait) = outcome.wait() {
+			self.wait_until = Some(unix_now() + wait.as_secs());
+		}
+		if *outcome == Outcome::Correct || outcome.is_wrong() {
+			self.guesses.entry((day, part_number(part)?)).or_default().push(Guess {
+				answer: answer.to_string(),
+				outcome: outcome.clone(),
+			});
+		}
+		Ok(())
+	}
+
+	pub fn save(&self) -> Result<()> {
+		let mut days = toml::value::Table::new();
+		if let Some(until) = self.wait_until {
+			days.insert(WAIT_KEY.to_string(), toml::Value::Integer(until as i64));
+		}
+		for ((day, part), guesses) in &self.guesses {
+			let guesses = guesses
+				.iter()
+				.map(|guess| {
+					let mut entry = toml::value::Table::new();
+					entry.insert("answer".to_string(), toml::Value::String(guess.answer.clone()));
+					entry.insert("outcome".to_string(), toml::Value::String(guess.outcome.to_string()));
+					toml::Value::Table(entry)
+				})
+				.collect();
+			let parts = days
+				.entry(day.to_string())
+				.or_insert_with(|| toml::Value::Table(toml::value::Table::new()));
+			if let toml::Value::Table(parts) = parts {
+				parts.insert(format!("part{}", part), toml::Value::Array(guesses));
+			}
+		}
+		let text = toml::to_string(&toml::Value::Table(days))?;
+		if let Some(parent) = self.path.parent() {
+			fs::create_dir_all(parent).wrap_err_with(|| format!("Couldn't create {}", parent.display()))?;
+		}
+		fs::write(&self.path, text).wrap_err_with(|| format!("Couldn't write {}", self.path.display()))
+	}
+}
+
+const WAIT_KEY: &str = "wait_until";
+
+fn unix_now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |now| now.as_secs())
+}
+
+fn part_number(part: Part) -> Result<u8> {
+	match part {
+		Part::Part1 => Ok(1),
+		Part::Part2 => Ok(2),
+		Part::Both => Err(eyre!("Guesses are for one part at a time")),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::env;
+
+	#[test]
+	fn test_check() {
+		let mut history = History::load_from(Path::new("does/not/exist.toml")).unwrap();
+		history.record(1, Part::Part1, "100", &Outcome::TooHigh).unwrap();
+		history.record(1, Part::Part1, "20", &Outcome::TooLow).unwrap();
+		history.record(1, Part::Part1, "50", &Outcome::Wrong).unwrap();
+		assert!(history.check(1, Part::Part1, "50").is_err());
+		assert!(history.check(1, Part::Part1, "100").is_err());
+		assert!(history.check(1, Part::Part1, "150").is_err());
+		assert!(history.check(1, Part::Part1, "10").is_err());
+		assert!(history.check(1, Part::Part1, "60").is_ok());
+		assert!(history.check(1, Part::Part1, "abc").is_ok());
+		assert!(history.check(1, Part::Part2, "50").is_ok());
+		history.record(1, Part::Part1, "60", &Outcome::Correct).unwrap();
+		assert!(history.check(1, Part::Part1, "61").is_err());
+	}
+
+	#[test]
+	fn test_wait() {
+		let mut history = History::load_from(Path::new("does/not/exist.toml")).unwrap();
+		assert_eq!(Outcome::Wait("4m 12s".to_string()).wait().map(|w| w.as_secs()), Some(252));
+		history.record(1, Part::Part1, "60", &Outcome::Wait("1m".to_string())).unwrap();
+		// Nothing goes until the time's up, and the answer that was too soon is still worth sending after
+		assert!(history.check(1, Part::Part1, "60").unwrap_err().to_string().contains("wait"));
+		assert!(history.guesses(1, Part::Part1).is_empty());
+		history.record(1, Part::Part1, "60", &Outcome::Wait("0s".to_string())).unwrap();
+		assert!(history.check(1, Part::Part1, "60").is_ok());
+	}
+
+	#[test]
+	fn test_save_and_load() {
+		let path = env::temp_dir().join(format!("aoc-guesses-{}.toml", std::process::id()));
+		let mut history = History::load_from(&path).unwrap();
+		history.record(13, Part::Part2, "42", &Outcome::TooLow).unwrap();
+		history.record(13, Part::Part2, "ZLBJF", &Outcome::Wrong).unwrap();
+		history.record(2, Part::Part1, "7", &Outcome::Correct).unwrap();
+		history.record(2, Part::Part2, "8", &Outcome::Wait("30s".to_string())).unwrap();
+		history.save().unwrap();
+		assert_eq!(History::load_from(&path).unwrap(), history);
+		assert_eq!(history.guesses(13, Part::Part2).len(), 2);
+		fs::remove_file(&path).unwrap();
+	}
+}
@@ -0,0 +1,95 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, keeping count of how much is allocated and the most there has been at once.
+/// A binary opts in with `#[global_allocator]`, without it every count here stays at 0
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+	fn grew(by: usize) {
+		let now = CURRENT.fetch_add(by, Ordering::Relaxed) + by;
+		PEAK.fetch_max(now, Ordering::Relaxed);
+	}
+
+	fn shrank(by: usize) {
+		CURRENT.fetch_sub(by, Ordering::Relaxed);
+	}
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		let ptr = System.alloc(layout);
+		if !ptr.is_null() {
+			CountingAllocator::grew(layout.size());
+		}
+		ptr
+	}
+
+	unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+		let ptr = System.alloc_zeroed(layout);
+		if !ptr.is_null() {
+			CountingAllocator::grew(layout.size());
+		}
+		ptr
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		System.dealloc(ptr, layout);
+		CountingAllocator::shrank(layout.size());
+	}
+
+	unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+		let new_ptr = System.realloc(ptr, layout, new_size);
+		if !new_ptr.is_null() {
+			if new_size > layout.size() {
+				CountingAllocator::grew(new_size - layout.size());
+			} else {
+				CountingAllocator::shrank(layout.size() - new_size);
+			}
+		}
+		new_ptr
+	}
+}
+
+/// Bytes allocated right now
+pub fn current() -> usize {
+	CURRENT.load(Ordering::Relaxed)
+}
+
+/// The most that's been allocated at once since the last `reset_peak`
+pub fn peak() -> usize {
+	PEAK.load(Ordering::Relaxed)
+}
+
+/// Start tracking a new peak from what's allocated now, which is handed back
+pub fn reset_peak() -> usize {
+	let now = current();
+	PEAK.store(now, Ordering::Relaxed);
+	now
+}
+
+/// Run something and say how far the heap grew above where it started while it ran
+pub fn peak_during<T>(f: impl FnOnce() -> T) -> (T, usize) {
+	let start = reset_peak();
+	let result = f();
+	(result, peak().saturating_sub(start))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[global_allocator]
+	static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+	#[test]
+	fn test_peak_during() {
+		let (buffer, peak) = peak_during(|| vec![1_u8; 1 << 20]);
+		assert_eq!(buffer.len(), 1 << 20);
+		// Other tests allocate at the same time, so this can only be a lower bound
+		assert!(peak >= 1 << 20, "{}", peak);
+	}
+}
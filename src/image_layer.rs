@@ -1,5 +1,6 @@
 use std::cmp;
 use std::collections::HashMap;
+use std::fmt;
 
 use crate::coords::Coords;
 
@@ -34,6 +35,7 @@ impl Pixel {
 	}
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ImageLayer {
 	pub pixels: Vec<Vec<Pixel>>,
 }
@@ -92,47 +94,40 @@ impl ImageLayer {
 		}
 		return (blacks, whites, transparents);
 	}
+}
 
-	pub fn to_string(&self) -> String {
-		let mut output: String =
-			String::with_capacity(self.pixels.len() * (self.pixels[0].len() + 1) + 1);
-		output.push_str("\n");
-		for row in self.pixels.iter() {
+impl fmt::Display for ImageLayer {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (y, row) in self.pixels.iter().enumerate() {
+			if y > 0 {
+				writeln!(f)?;
+			}
 			for px in row.iter() {
-				match px {
+				let shown = match px {
 					Pixel::Black => {
 						if !DARK_MODE {
-							output.push_str(BLOCK);
+							BLOCK
 						} else {
 							//Non-breaking space
-							output.push_str(" ");
+							" "
 						}
 					}
 					Pixel::White => {
 						if DARK_MODE {
-							output.push_str(BLOCK);
+							BLOCK
 						} else {
 							//Non-breaking space
-							output.push_str(" ");
+							" "
 						}
 					},
-					Pixel::Star => {
-						output.push_str("*")
-					},
-					Pixel::Transparent => {
-						output.push_str("@")
-					},
-					Pixel::CapP => {
-						output.push_str("P");
-					},
-					Pixel::LowP => {
-						output.push_str("p");
-					}
-					_ => unreachable!(),
-				}
+					Pixel::Star => "*",
+					Pixel::Transparent => "@",
+					Pixel::CapP => "P",
+					Pixel::LowP => "p",
+				};
+				f.write_str(shown)?;
 			}
-			output.push_str("\n");
 		}
-		return output;
+		Ok(())
 	}
-}
\ No newline at end of file
+}
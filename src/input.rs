@@ -0,0 +1,132 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use crate::site;
+
+/// Where inputs live when nobody tells us otherwise, relative to the working directory
+pub const INPUT_DIR: &str = "inputs";
+
+/// Passing this as the input path means "read it from stdin"
+pub const STDIN_MARKER: &str = "-";
+
+/// The conventional place for a day's input: `inputs/<year>/Day<N>.txt`
+pub fn default_path(year: u32, day: u32) -> PathBuf {
+	path_in(Path::new(INPUT_DIR), year, day)
+}
+
+/// Whether there's an input at `path`, an empty file is just a placeholder
+pub fn is_non_empty_file(path: &Path) -> bool {
+	fs::metadata(path).is_ok_and(|m| m.is_file() && m.len() > 0)
+}
+
+/// Where a day's input goes in a directory laid out like `inputs`
+pub fn path_in(dir: &Path, year: u32, day: u32) -> PathBuf {
+	dir.join(year.to_string()).join(format!("Day{}.txt", day))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+	File(PathBuf),
+	Stdin,
+	/// Not on disk yet, but there's a session token to download it with
+	Download { year: u32, day: u32 },
+}
+
+impl InputSource {
+	/// Work out where the input for a day should come from.
+	///
+	/// An explicit path always wins (with `-` meaning stdin), then the conventional
+	/// file, and failing that it gets downloaded, if we're logged in. Stdin is only
+	/// read when asked for, so nothing run from a script or cron sits reading an empty one.
+	pub fn resolve(explicit: Option<&Path>, year: u32, day: u32) -> Result<InputSource> {
+		if let Some(path) = explicit {
+			return Ok(if path == Path::new(STDIN_MARKER) {
+				InputSource::Stdin
+			} else {
+				InputSource::File(path.to_path_buf())
+			});
+		}
+		let conventional = default_path(year, day);
+		if is_non_empty_file(&conventional) {
+			Ok(InputSource::File(conventional))
+		} else if site::session_token()?.is_some() {
+			Ok(InputSource::Download { year, day })
+		} else if conventional.is_file() {
+			// Left empty by `new` for pasting into, nothing better to read
+			Ok(InputSource::File(conventional))
+		} else {
+			Err(eyre!(
+				"No input for {} day {}: {} does not exist, pass --input <path> (or - for stdin) or set ${} to download it",
+				year,
+				day,
+				conventional.display(),
+				site::SESSION_VAR
+			))
+		}
+	}
+
+	pub fn read(&self) -> Result<String> {
+		match self {
+			InputSource::File(path) => fs::read_to_string(path)
+				.wrap_err_with(|| format!("Could not read input from {}", path.display())),
+			InputSource::Stdin => {
+				let mut buffer = String::new();
+				io::stdin()
+					.read_to_string(&mut buffer)
+					.wrap_err("Could not read input from stdin")?;
+				Ok(buffer)
+			}
+			InputSource::Download { year, day } => {
+				let path = site::Site::from_env()?.fetch(*year, *day)?;
+				InputSource::File(path).read()
+			}
+		}
+	}
+}
+
+/// Resolve and read the input for a day in one go
+pub fn load(explicit: Option<&Path>, year: u32, day: u32) -> Result<String> {
+	InputSource::resolve(explicit, year, day)?.read()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_default_path() {
+		assert_eq!(default_path(2019, 7), Path::new("inputs/2019/Day7.txt"));
+	}
+
+	#[test]
+	fn test_explicit_wins() {
+		let explicit = PathBuf::from("somewhere/else.txt");
+		assert_eq!(
+			InputSource::resolve(Some(&explicit), 2019, 1).unwrap(),
+			InputSource::File(explicit)
+		);
+		assert_eq!(
+			InputSource::resolve(Some(Path::new("-")), 2019, 1).unwrap(),
+			InputSource::Stdin
+		);
+	}
+
+	#[test]
+	fn test_conventional_location() {
+		assert_eq!(
+			InputSource::resolve(None, 2019, 1).unwrap(),
+			InputSource::File(default_path(2019, 1))
+		);
+		assert!(load(None, 2019, 1).unwrap().lines().count() > 0);
+	}
+
+	#[test]
+	fn test_stdin_only_when_asked() {
+		// There's no day 24 file, and whatever stdin the tests have isn't taken for it
+		assert!(!default_path(2019, 24).exists());
+		assert_ne!(InputSource::resolve(None, 2019, 24).ok(), Some(InputSource::Stdin));
+	}
+}
@@ -1,8 +1,135 @@
 pub type NumType = i64;
 
-use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+use std::{any::Any, collections::VecDeque, error::Error, fmt, str::FromStr};
+
+use io::{Input, Output, Queue};
+use snapshot::{Queues, Snapshot};
+use trace::{TraceStep, Tracer};
+
+pub mod arcade;
+pub mod ascii;
+pub mod asm;
+pub mod debug;
+pub mod disasm;
+pub mod io;
+pub mod network;
+pub mod snapshot;
+pub mod topology;
+pub mod trace;
 
 pub type Memory = Vec<NumType>;
+
+/// Where a program's source stopped making sense, lines and columns count from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+	pub line: usize,
+	pub column: usize,
+	/// The offending value, empty if one was missing altogether
+	pub token: String,
+}
+
+impl fmt::Display for ParseError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.token.is_empty() {
+			write!(f, "line {}, column {}: expected an intcode value", self.line, self.column)
+		} else {
+			write!(f, "line {}, column {}: {:?} is not a valid intcode value", self.line, self.column, self.token)
+		}
+	}
+}
+
+impl Error for ParseError {}
+
+/// How far out a program can write before it's treated as a bad address rather than a reason to
+/// allocate gigabytes
+pub const MAX_MEMORY: usize = 1 << 24;
+
+/// Why a program couldn't carry on. `ip` is where the faulting instruction starts and `instruction`
+/// is its raw value, modes and all
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VmError {
+	/// The instruction pointer ran off the end of memory
+	OutOfBounds { ip: usize },
+	UnknownOpcode { ip: usize, instruction: NumType },
+	UnknownMode { ip: usize, instruction: NumType, mode: NumType },
+	/// A read, write or jump to a negative address, or a write past `MAX_MEMORY`
+	BadAddress { ip: usize, instruction: NumType, address: NumType },
+	ImmediateWrite { ip: usize, instruction: NumType },
+	/// Arithmetic, or the relative base, went past what a `NumType` holds
+	Overflow { ip: usize, instruction: NumType },
+	/// Stopped for input when it all had to be given up front
+	NeedsInput { ip: usize },
+}
+
+impl fmt::Display for VmError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			VmError::OutOfBounds { ip } => write!(f, "ip {}: past the end of memory", ip),
+			VmError::UnknownOpcode { ip, instruction } => {
+				write!(f, "ip {}: {} has an unknown opcode {}", ip, instruction, instruction % 100)
+			}
+			VmError::UnknownMode { ip, instruction, mode } => write!(f, "ip {}: {} has an unknown mode {}", ip, instruction, mode),
+			VmError::BadAddress { ip, instruction, address } => write!(f, "ip {}: {} used a bad address {}", ip, instruction, address),
+			VmError::ImmediateWrite { ip, instruction } => write!(f, "ip {}: {} writes to an immediate parameter", ip, instruction),
+			VmError::Overflow { ip, instruction } => write!(f, "ip {}: {} overflowed", ip, instruction),
+			VmError::NeedsInput { ip } => write!(f, "ip {}: waiting for input that was never given", ip),
+		}
+	}
+}
+
+impl Error for VmError {}
+
+/// Read a program from its usual comma separated text form.
+/// Any whitespace (newlines included) can go around the values, and a trailing comma is fine
+pub fn memory_from_str(input: &str) -> Result<Memory, ParseError> {
+	let mut memory = Vec::new();
+	let (mut line, mut column) = (1, 1);
+	// The value being read, and where it started
+	let mut token = String::new();
+	let mut start = (1, 1);
+	// Whether there's been whitespace since the value started, "1 2" is two values missing a comma
+	let mut gap = false;
+	let finish = |token: &mut String, start: (usize, usize), memory: &mut Memory| {
+		let value = token.parse().map_err(|_| ParseError { line: start.0, column: start.1, token: token.clone() })?;
+		memory.push(value);
+		token.clear();
+		Ok(())
+	};
+	for c in input.chars() {
+		match c {
+			',' if token.is_empty() => return Err(ParseError { line, column, token: String::new() }),
+			',' => {
+				finish(&mut token, start, &mut memory)?;
+				gap = false;
+			}
+			c if c.is_whitespace() => gap = !token.is_empty(),
+			c => {
+				if token.is_empty() {
+					start = (line, column);
+				} else if gap {
+					// Report the whole run of values, it's more use than just the first
+					token.push(' ');
+					gap = false;
+				}
+				token.push(c);
+			}
+		}
+		if c == '\n' {
+			line += 1;
+			column = 1;
+		} else {
+			column += 1;
+		}
+	}
+	if !token.is_empty() {
+		finish(&mut token, start, &mut memory)?;
+	}
+	if memory.is_empty() {
+		return Err(ParseError { line, column, token: String::new() });
+	}
+	Ok(memory)
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum State {
 	Ready,
@@ -24,12 +151,12 @@ pub enum Mode {
 }
 
 impl Mode {
-	fn convert_int(value: NumType) -> Mode {
+	fn convert_int(value: NumType) -> Option<Mode> {
 		match value {
-			0 => Mode::Position,
-			1 => Mode::Immediate,
-			2 => Mode::Relative,
-			_ => unreachable!(),
+			0 => Some(Mode::Position),
+			1 => Some(Mode::Immediate),
+			2 => Some(Mode::Relative),
+			_ => None,
 		}
 	}
 }
@@ -39,6 +166,20 @@ impl Default for Mode {
 	}
 }
 
+const OPCODES: [Opcode; 10] = [
+	Opcode::Add,
+	Opcode::Multiply,
+	Opcode::Input,
+	Opcode::Output,
+	Opcode::JumpIfTrue,
+	Opcode::JumpIfFalse,
+	Opcode::LessThan,
+	Opcode::Equals,
+	Opcode::AdjustRel,
+	Opcode::Stop,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Opcode {
 	Add = 1,
 	Multiply = 2,
@@ -53,23 +194,103 @@ pub enum Opcode {
 }
 
 impl Opcode {
-	fn convert_int(value: NumType) -> Opcode {
+	fn convert_int(value: NumType) -> Option<Opcode> {
 		match value {
-			1 => Opcode::Add,
-			2 => Opcode::Multiply,
-			3 => Opcode::Input,
-			4 => Opcode::Output,
-			5 => Opcode::JumpIfTrue,
-			6 => Opcode::JumpIfFalse,
-			7 => Opcode::LessThan,
-			8 => Opcode::Equals,
-			9 => Opcode::AdjustRel,
-			99 => Opcode::Stop,
-			_ => panic!("Not a valid op code - {}", value),
+			1 => Some(Opcode::Add),
+			2 => Some(Opcode::Multiply),
+			3 => Some(Opcode::Input),
+			4 => Some(Opcode::Output),
+			5 => Some(Opcode::JumpIfTrue),
+			6 => Some(Opcode::JumpIfFalse),
+			7 => Some(Opcode::LessThan),
+			8 => Some(Opcode::Equals),
+			9 => Some(Opcode::AdjustRel),
+			99 => Some(Opcode::Stop),
+			_ => None,
+		}
+	}
+
+	/// How many parameters follow it
+	pub fn params(self) -> usize {
+		match self {
+			Opcode::Add | Opcode::Multiply | Opcode::LessThan | Opcode::Equals => 3,
+			Opcode::JumpIfTrue | Opcode::JumpIfFalse => 2,
+			Opcode::Input | Opcode::Output | Opcode::AdjustRel => 1,
+			Opcode::Stop => 0,
+		}
+	}
+
+	/// Which parameter is written to, that one can't be immediate
+	pub fn writes(self) -> Option<usize> {
+		match self {
+			Opcode::Add | Opcode::Multiply | Opcode::LessThan | Opcode::Equals => Some(2),
+			Opcode::Input => Some(0),
+			_ => None,
+		}
+	}
+
+	pub fn from_mnemonic(mnemonic: &str) -> Option<Opcode> {
+		OPCODES.iter().copied().find(|opcode| opcode.mnemonic() == mnemonic)
+	}
+
+	pub fn mnemonic(self) -> &'static str {
+		match self {
+			Opcode::Add => "add",
+			Opcode::Multiply => "mul",
+			Opcode::Input => "in",
+			Opcode::Output => "out",
+			Opcode::JumpIfTrue => "jt",
+			Opcode::JumpIfFalse => "jf",
+			Opcode::LessThan => "lt",
+			Opcode::Equals => "eq",
+			Opcode::AdjustRel => "arb",
+			Opcode::Stop => "hlt",
 		}
 	}
 }
 
+/// How the VM gets from an instruction's value to what it should do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Engine {
+	/// Work out the opcode and modes every time an instruction runs
+	Interpreter,
+	/// Work each instruction out once and keep it by address. Writes from the program (or `poke`) forget what was
+	/// kept for where they land, so code that writes over itself just gets worked out again. Anything else changing
+	/// `memory` directly should call `set_engine` after to start afresh
+	#[default]
+	Cached,
+}
+
+/// An instruction's opcode and modes, split out of its value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Decoded {
+	raw: NumType,
+	opcode: Opcode,
+	/// `None` where the digit isn't a mode. That's only an error if the parameter gets used
+	modes: [Option<Mode>; 3],
+}
+
+impl Decoded {
+	fn new(ip: usize, raw: NumType) -> Result<Decoded, VmError> {
+		let opcode = Opcode::convert_int(raw % 100).ok_or(VmError::UnknownOpcode { ip, instruction: raw })?;
+		let mut modes = [None; 3];
+		let mut rest = raw / 100;
+		for mode in modes.iter_mut() {
+			*mode = Mode::convert_int(rest % 10);
+			rest /= 10;
+		}
+		Ok(Decoded { raw, opcode, modes })
+	}
+
+	fn mode(&self, ip: usize, param: usize) -> Result<Mode, VmError> {
+		self.modes[param].ok_or_else(|| VmError::UnknownMode {
+			ip,
+			instruction: self.raw,
+			mode: self.raw / 10_i64.pow(param as u32 + 2) % 10,
+		})
+	}
+}
+
 #[derive(Debug)]
 pub struct IntCodeVM {
 	pub memory: Memory,
@@ -77,71 +298,88 @@ pub struct IntCodeVM {
 	pub state: State,
 	pub mode: Mode,
 	pub relative: NumType,
-	input: Rc<RefCell<VecDeque<NumType>>>,
-	output: Rc<RefCell<VecDeque<NumType>>>,
+	input: Queue,
+	output: Queue,
+	/// Where input comes from once the queue's empty, and where output goes instead of the queue
+	source: Hook<dyn Input>,
+	sink: Hook<dyn Output>,
+	tracer: Hook<dyn Tracer>,
+	engine: Engine,
+	/// For `Engine::Cached`, by address
+	decoded: Vec<Option<Decoded>>,
+}
+
+/// Somewhere for something optional plugged into the VM to live that doesn't stop the VM being `Debug`
+struct Hook<T: ?Sized>(Option<Box<T>>);
+
+impl<T: ?Sized> Default for Hook<T> {
+	fn default() -> Self {
+		Hook(None)
+	}
+}
+
+impl<T: ?Sized> fmt::Debug for Hook<T> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(if self.0.is_some() { "Hook(on)" } else { "Hook(off)" })
+	}
 }
 
 impl Clone for IntCodeVM{
 	fn clone(&self) -> Self {
-		let inp: VecDeque<NumType> = VecDeque::new();
-		let out: VecDeque<NumType> = VecDeque::new();
 		Self {
 			memory: self.memory.clone(),
-			register: self.register.clone(),
-			relative: self.relative.clone(),
-			state: self.state.clone(),
-			mode: self.mode.clone(),
-			input: Rc::new(RefCell::new(inp)),
-			output: Rc::new(RefCell::new(out))
+			register: self.register,
+			relative: self.relative,
+			state: self.state,
+			mode: self.mode,
+			input: Queue::new(),
+			output: Queue::new(),
+			source: Hook::default(),
+			sink: Hook::default(),
+			tracer: Hook::default(),
+			engine: self.engine,
+			decoded: self.decoded.clone(),
 		}
 	}
 }
 
-fn safer_unsigned_add(u: usize, i: NumType) -> usize{
-	if i.is_negative() {
-        u.checked_sub(i.wrapping_abs() as u64 as usize).unwrap()
-    } else {
-        u.checked_add(i as usize).unwrap()
-    }
-}
+impl FromStr for IntCodeVM {
+	type Err = ParseError;
 
-fn signed_add_to_unsigned(a: NumType, b: NumType) -> usize {
-	if a.is_negative() && b.is_negative(){
-		panic!("Cannot add two negative numbers to make an unsigned number");
-	}else if a.is_negative(){
-		safer_unsigned_add(b as usize, a)
-	}else{
-		safer_unsigned_add(a as usize, b)
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(IntCodeVM::new(memory_from_str(s)?))
 	}
 }
 
 impl IntCodeVM {
 	pub fn new(memory: Memory) -> Self {
-		let inp: VecDeque<NumType> = VecDeque::new();
-		let out: VecDeque<NumType> = VecDeque::new();
 		Self {
 			memory,
 			register: 0,
 			relative: 0,
 			state: State::default(),
 			mode: Mode::default(),
-			input: Rc::new(RefCell::new(inp)),
-			output: Rc::new(RefCell::new(out)),
+			input: Queue::new(),
+			output: Queue::new(),
+			source: Hook::default(),
+			sink: Hook::default(),
+			tracer: Hook::default(),
+			engine: Engine::default(),
+			decoded: Vec::new(),
 		}
 	}
 
-	pub fn new_run_all_output(memory: Memory) -> VecDeque<NumType> {
+	pub fn new_run_all_output(memory: Memory) -> Result<VecDeque<NumType>, VmError> {
 		let mut vm = IntCodeVM::new(memory);
-		vm.run_all();
+		vm.run_all()?;
 		if vm.is_stopped() {
-			return vm.output().take();
+			Ok(vm.output().take())
 		}else{
-			panic!("Cannot deal with input as new_run_all_output");
+			Err(VmError::NeedsInput { ip: vm.register })
 		}
 	}
 
 	pub fn new_networked(memory: Memory, input_from: &IntCodeVM) -> Self {
-		let out: VecDeque<NumType> = VecDeque::new();
 		Self {
 			memory,
 			register: 0,
@@ -149,110 +387,311 @@ impl IntCodeVM {
 			state: State::default(),
 			mode: Mode::default(),
 			input: input_from.output(),
-			output: Rc::new(RefCell::new(out)),
+			output: Queue::new(),
+			source: Hook::default(),
+			sink: Hook::default(),
+			tracer: Hook::default(),
+			engine: Engine::default(),
+			decoded: Vec::new(),
 		}
 	}
 
 	pub fn push_input(&mut self, entry: NumType) {
-		self.input.borrow_mut().push_back(entry);
+		self.input.push(entry);
 		if self.state == State::Reading {
 			//We should be right to keep running!
 			self.state = State::Ready;
 		}
 	}
 
-	pub fn output(&self) -> Rc<RefCell<VecDeque<NumType>>> {
-		Rc::clone(&self.output)
+	pub fn output(&self) -> Queue {
+		self.output.clone()
 	}
 
 	pub fn pop_output(&mut self) -> Option<NumType>{
-		self.output.borrow_mut().pop_front()
+		self.output.pop()
 	}
 
 	pub fn get_zero(&self) -> NumType {
 		return self.memory[0];
 	}
 
-	pub fn run_all(&mut self) {
+	/// Run until the program stops or wants input it doesn't have, and say which
+	pub fn run_all(&mut self) -> Result<State, VmError> {
 		loop {
 			match self.state {
 				State::Ready => {
-					self.run_one_command();
+					self.run_one_command()?;
 				}
 				State::Stopped => {
-					break;
+					return Ok(State::Stopped);
 				}
 				State::Reading => {
-					if self.input.borrow().len() > 0 {
+					if self.input_ready() {
 						self.state = State::Ready;
 					} else {
-						break;
+						return Ok(State::Reading);
 					}
 				}
 			}
 		}
 	}
 
-	fn read_mem(&self, index: usize, mode: Mode) -> NumType {
-		match mode {
-			Mode::Immediate => *self.memory.get(index).unwrap_or(&0),
-			Mode::Position => *self
-				.memory
-				.get(*self.memory.get(index).unwrap_or(&0) as usize)
-				.unwrap_or(&0),
-			Mode::Relative => {
-				let oindx = *self.memory.get(index).unwrap_or(&0);
-				let nindx = signed_add_to_unsigned(oindx, self.relative);
-				return *self
-					.memory
-					.get(nindx)
-					.unwrap_or(&0);
+	/// The instruction being run, for errors. Only called once it's known to be in memory
+	fn instruction(&self) -> NumType {
+		self.memory[self.register]
+	}
+
+	fn bad_address(&self, address: NumType) -> VmError {
+		VmError::BadAddress { ip: self.register, instruction: self.instruction(), address }
+	}
+
+	fn overflow(&self) -> VmError {
+		VmError::Overflow { ip: self.register, instruction: self.instruction() }
+	}
+
+	/// Where a parameter points, immediate parameters don't point anywhere
+	fn address(&self, index: usize, mode: Mode) -> Result<usize, VmError> {
+		let raw = *self.memory.get(index).unwrap_or(&0);
+		let address = match mode {
+			Mode::Position => raw,
+			Mode::Relative => self.relative.checked_add(raw).ok_or_else(|| self.overflow())?,
+			Mode::Immediate => {
+				return Err(VmError::ImmediateWrite { ip: self.register, instruction: self.instruction() })
 			}
+		};
+		if address.is_negative() {
+			return Err(self.bad_address(address));
 		}
+		Ok(address as usize)
 	}
 
-	fn write_mem(&mut self, index: usize, mode: Mode, value: NumType) {
-		match mode {
-			Mode::Position => {
-				let rel_index = self.read_mem(index, Mode::Immediate) as usize;
-				if self.memory.len() <= rel_index {
-					self.memory.resize(rel_index + 1, 0);
-				}
-				self.memory[rel_index] = value
+	fn read_mem(&self, index: usize, mode: Mode) -> Result<NumType, VmError> {
+		if mode == Mode::Immediate {
+			return Ok(*self.memory.get(index).unwrap_or(&0));
+		}
+		Ok(*self.memory.get(self.address(index, mode)?).unwrap_or(&0))
+	}
+
+	/// Where a parameter points, as long as it can be written to
+	fn write_address(&self, index: usize, mode: Mode) -> Result<usize, VmError> {
+		let address = self.address(index, mode)?;
+		if address >= MAX_MEMORY {
+			return Err(self.bad_address(address as NumType));
+		}
+		Ok(address)
+	}
+
+	fn write_mem(&mut self, index: usize, mode: Mode, value: NumType) -> Result<(), VmError> {
+		let address = self.write_address(index, mode)?;
+		self.poke(address, value);
+		Ok(())
+	}
+
+	/// Change memory from outside the program, growing it if needed. Unlike writing to `memory` this keeps the
+	/// cached engine right. Addresses aren't checked against `MAX_MEMORY`, that's up to the caller
+	pub fn poke(&mut self, address: usize, value: NumType) {
+		if self.memory.len() <= address {
+			self.memory.resize(address + 1, 0);
+		}
+		self.memory[address] = value;
+		if let Some(decoded) = self.decoded.get_mut(address) {
+			*decoded = None;
+		}
+	}
+
+	fn jump_target(&self, index: usize, mode: Mode) -> Result<usize, VmError> {
+		let target = self.read_mem(index, mode)?;
+		if target.is_negative() {
+			return Err(self.bad_address(target));
+		}
+		Ok(target as usize)
+	}
+
+	pub fn engine(&self) -> Engine {
+		self.engine
+	}
+
+	/// Switch how instructions are run, which can be done at any point
+	pub fn set_engine(&mut self, engine: Engine) {
+		self.engine = engine;
+		self.decoded = Vec::new();
+	}
+
+	/// The instruction at the instruction pointer
+	fn fetch(&mut self) -> Result<Decoded, VmError> {
+		let ip = self.register;
+		if self.engine == Engine::Cached {
+			// Anything kept is still right, writes drop it
+			if let Some(Some(decoded)) = self.decoded.get(ip) {
+				return Ok(*decoded);
 			}
-			Mode::Relative => {
-				let rel_index = self.read_mem(index, Mode::Immediate);
-				let nindx = signed_add_to_unsigned(rel_index, self.relative);
-				if self.memory.len() <= nindx {
-					self.memory.resize(nindx + 1, 0);
-				}
-				self.memory[nindx] = value
+		}
+		let raw = *self.memory.get(ip).ok_or(VmError::OutOfBounds { ip })?;
+		if self.engine == Engine::Interpreter {
+			return Decoded::new(ip, raw);
+		}
+		let decoded = Decoded::new(ip, raw)?;
+		if self.decoded.len() <= ip {
+			self.decoded.resize(self.memory.len().max(ip + 1), None);
+		}
+		self.decoded[ip] = Some(decoded);
+		Ok(decoded)
+	}
+
+	/// Read from somewhere other than the input queue once it's empty. Anything pushed is still read first
+	pub fn set_input<I: Input + 'static>(&mut self, input: I) {
+		self.source.0 = Some(Box::new(input));
+	}
+
+	/// Write somewhere other than the output queue, which stays empty from now on
+	pub fn set_output<O: Output + 'static>(&mut self, output: O) {
+		self.sink.0 = Some(Box::new(output));
+	}
+
+	/// Whether there's input to read, fetching a value from the input source if the queue's empty
+	fn input_ready(&mut self) -> bool {
+		if !self.input.is_empty() {
+			return true;
+		}
+		match self.source.0.as_mut().and_then(|source| source.try_read()) {
+			Some(value) => {
+				self.input.push(value);
+				true
 			}
-			_ => unreachable!(),
+			None => false,
 		}
 	}
 
-	pub fn run_one_command(&mut self) {
-		let command = self.memory[self.register];
+	/// Like `run_all`, but wait on the input source whenever it's out of input. Only gives up waiting if the source
+	/// says there'll be no more, which for a channel is once every sender's gone
+	pub fn run_blocking(&mut self) -> Result<State, VmError> {
+		loop {
+			if self.run_all()? == State::Stopped {
+				return Ok(State::Stopped);
+			}
+			match self.source.0.as_mut().and_then(|source| source.read()) {
+				Some(value) => self.push_input(value),
+				None => return Ok(State::Reading),
+			}
+		}
+	}
+
+	/// Where it's got to, without what's queued. Unlike `clone` it can be kept, or saved, and put back with `restore`
+	pub fn snapshot(&self) -> Snapshot {
+		Snapshot {
+			memory: self.memory.clone(),
+			register: self.register,
+			relative: self.relative,
+			state: self.state,
+			io: None,
+		}
+	}
+
+	/// A snapshot including the input and output waiting in its queues
+	pub fn snapshot_with_io(&self) -> Snapshot {
+		let io = Queues { input: self.input.lock().iter().copied().collect(), output: self.output.lock().iter().copied().collect() };
+		Snapshot { io: Some(io), ..self.snapshot() }
+	}
+
+	/// Go back to a snapshot. The queues are only changed if it has them, and they're filled in place so any
+	/// VM sharing them sees it. Anything plugged in (tracer, input source, output sink) stays
+	pub fn restore(&mut self, snapshot: &Snapshot) {
+		self.memory = snapshot.memory.clone();
+		self.decoded = Vec::new();
+		self.register = snapshot.register;
+		self.relative = snapshot.relative;
+		self.state = snapshot.state;
+		if let Some(io) = &snapshot.io {
+			*self.input.lock() = io.input.iter().copied().collect();
+			*self.output.lock() = io.output.iter().copied().collect();
+		}
+	}
+
+	pub fn from_snapshot(snapshot: &Snapshot) -> Self {
+		let mut vm = IntCodeVM::new(Vec::new());
+		vm.restore(snapshot);
+		vm
+	}
+
+	/// Have every instruction run from now on reported to a tracer, replacing any there was
+	pub fn set_tracer<T: Tracer>(&mut self, tracer: T) {
+		self.tracer.0 = Some(Box::new(tracer));
+	}
+
+	/// Have a look at the tracer, if it's a `T`
+	pub fn tracer<T: Tracer>(&self) -> Option<&T> {
+		let tracer: &dyn Any = self.tracer.0.as_deref()?;
+		tracer.downcast_ref()
+	}
+
+	/// Stop tracing and get the tracer back, if it's a `T`. If it isn't it's left where it is
+	pub fn take_tracer<T: Tracer>(&mut self) -> Option<T> {
+		let is_t = self.tracer::<T>().is_some();
+		let tracer: Box<dyn Any> = self.tracer.0.take().filter(|_| is_t)?;
+		tracer.downcast().ok().map(|tracer| *tracer)
+	}
+
+	/// Run a single instruction. If it fails nothing has changed, the instruction pointer is left on it
+	pub fn run_one_command(&mut self) -> Result<(), VmError> {
+		if self.tracer.0.is_none() {
+			return self.execute();
+		}
+		let step = self.start_step();
+		self.execute()?;
+		if let Some(mut step) = step {
+			// Stopping to wait for input isn't a step
+			if self.state == State::Reading && self.register == step.ip {
+				return Ok(());
+			}
+			step.write = step.write.map(|(address, _)| (address, self.memory[address]));
+			step.next = self.register;
+			if let Some(tracer) = self.tracer.0.as_mut() {
+				tracer.trace(&step);
+			}
+		}
+		Ok(())
+	}
 
-		let opcode = Opcode::convert_int(command % 100);
-		let mut mode_raw = command / 100;
-		let mut modes_find = || {
-			let mode = mode_raw % 10;
-			mode_raw /= 10;
-			Mode::convert_int(mode)
+	/// What's about to run and what it reads, for the tracer. Anything that can't be worked out will
+	/// fail to run anyway
+	fn start_step(&self) -> Option<TraceStep> {
+		let instruction = disasm::decode(&self.memory, self.register)?;
+		let writes = instruction.opcode.writes();
+		let read = match instruction.opcode {
+			Opcode::JumpIfTrue | Opcode::JumpIfFalse => 1,
+			_ => instruction.params.len(),
 		};
-		let next_instruction = match opcode {
+		let mut reads = Vec::with_capacity(read);
+		for (i, param) in instruction.params.iter().enumerate().take(read) {
+			if writes != Some(i) {
+				reads.push(self.read_mem(self.register + 1 + i, param.mode).ok()?);
+			}
+		}
+		let write = match writes {
+			Some(i) => Some((self.address(self.register + 1 + i, instruction.params[i].mode).ok()?, 0)),
+			None => None,
+		};
+		Some(TraceStep { ip: self.register, instruction, reads, write, next: self.register })
+	}
+
+	fn execute(&mut self) -> Result<(), VmError> {
+		let decoded = self.fetch()?;
+		let ip = self.register;
+		let mode = |i| decoded.mode(ip, i);
+		let next_instruction = match decoded.opcode {
 			Opcode::Add => {
-				let a = self.read_mem(self.register + 1, modes_find());
-				let b = self.read_mem(self.register + 2, modes_find());
-				self.write_mem(self.register + 3, modes_find(), a + b);
+				let a = self.read_mem(self.register + 1, mode(0)?)?;
+				let b = self.read_mem(self.register + 2, mode(1)?)?;
+				let sum = a.checked_add(b).ok_or_else(|| self.overflow())?;
+				self.write_mem(self.register + 3, mode(2)?, sum)?;
 				self.register + 4
 			}
 			Opcode::Multiply => {
-				let a = self.read_mem(self.register + 1, modes_find());
-				let b = self.read_mem(self.register + 2, modes_find());
-				self.write_mem(self.register + 3, modes_find(), a * b);
+				let a = self.read_mem(self.register + 1, mode(0)?)?;
+				let b = self.read_mem(self.register + 2, mode(1)?)?;
+				let product = a.checked_mul(b).ok_or_else(|| self.overflow())?;
+				self.write_mem(self.register + 3, mode(2)?, product)?;
 				self.register + 4
 			}
 			Opcode::Stop => {
@@ -260,15 +699,18 @@ impl IntCodeVM {
 				self.register + 1
 			}
 			Opcode::AdjustRel => {
-				let adjust_by = self.read_mem(self.register + 1, modes_find());
-				self.relative += adjust_by;
+				let adjust_by = self.read_mem(self.register + 1, mode(0)?)?;
+				self.relative = self.relative.checked_add(adjust_by).ok_or_else(|| self.overflow())?;
 				self.register + 2
 			}
 			Opcode::Input => {
-				let opt: Option<NumType> = self.input.borrow_mut().pop_front();
+				let to = mode(0)?;
+				// Check it can be written before taking the input, so a failure doesn't lose it
+				self.write_address(self.register + 1, to)?;
+				let opt: Option<NumType> = if self.input_ready() { self.input.pop() } else { None };
 				match opt {
 					Some(nvar) => {
-						self.write_mem(self.register + 1, modes_find(), nvar);
+						self.write_mem(self.register + 1, to, nvar)?;
 						self.register + 2
 					}
 					None => {
@@ -278,47 +720,49 @@ impl IntCodeVM {
 				}
 			}
 			Opcode::Output => {
-				self.output
-					.borrow_mut()
-					.push_back(self.read_mem(self.register + 1, modes_find()));
+				let value = self.read_mem(self.register + 1, mode(0)?)?;
+				match self.sink.0.as_mut() {
+					Some(sink) => sink.write(value),
+					None => self.output.push(value),
+				}
 				self.register + 2
 			}
 			Opcode::JumpIfTrue => {
-				let act = self.read_mem(self.register + 1, modes_find());
+				let act = self.read_mem(self.register + 1, mode(0)?)?;
 				if act != 0 {
-					self.read_mem(self.register + 2, modes_find()) as usize
+					self.jump_target(self.register + 2, mode(1)?)?
 				} else {
 					self.register + 3
 				}
 			}
 			Opcode::JumpIfFalse => {
-				let act = self.read_mem(self.register + 1, modes_find());
+				let act = self.read_mem(self.register + 1, mode(0)?)?;
 				if act == 0 {
-					self.read_mem(self.register + 2, modes_find()) as usize
+					self.jump_target(self.register + 2, mode(1)?)?
 				} else {
 					self.register + 3
 				}
 			}
 			Opcode::LessThan => {
-				let a = self.read_mem(self.register + 1, modes_find());
-				let b = self.read_mem(self.register + 2, modes_find());
-				self.write_mem(self.register + 3, modes_find(), if a < b { 1 } else { 0 });
+				let a = self.read_mem(self.register + 1, mode(0)?)?;
+				let b = self.read_mem(self.register + 2, mode(1)?)?;
+				self.write_mem(self.register + 3, mode(2)?, if a < b { 1 } else { 0 })?;
 				self.register + 4
 			}
 			Opcode::Equals => {
-				let a = self.read_mem(self.register + 1, modes_find());
-				let b = self.read_mem(self.register + 2, modes_find());
-				self.write_mem(self.register + 3, modes_find(), if a == b { 1 } else { 0 });
+				let a = self.read_mem(self.register + 1, mode(0)?)?;
+				let b = self.read_mem(self.register + 2, mode(1)?)?;
+				self.write_mem(self.register + 3, mode(2)?, if a == b { 1 } else { 0 })?;
 				self.register + 4
 			}
-		} as usize;
+		};
 		self.register = next_instruction;
-		return;
+		Ok(())
 	}
 
 	/// Get a reference to the int code vm's input.
-	pub fn input(&self) -> Rc<RefCell<VecDeque<NumType>>> {
-		Rc::clone(&self.input)
+	pub fn input(&self) -> Queue {
+		self.input.clone()
 	}
 
 	pub fn network_to(&mut self, other: &IntCodeVM) {
@@ -338,11 +782,11 @@ impl IntCodeVM {
 	}
 
 	pub fn has_input(&self) -> bool {
-		self.input.borrow().len() > 0
+		!self.input.is_empty()
 	}
 
 	pub fn has_output_amt(&self, amt: usize) -> bool {
-		self.output.borrow().len() >= amt
+		self.output.len() >= amt
 	}
 }
 
@@ -350,18 +794,200 @@ impl IntCodeVM {
 mod tests {
 	use super::*;
 
+	#[test]
+	fn test_memory_from_str() {
+		assert_eq!(memory_from_str("1,0,0,3,99").unwrap(), vec![1, 0, 0, 3, 99]);
+		assert_eq!(memory_from_str(" 1, -2 ,\n3,\r\n4 ,\n").unwrap(), vec![1, -2, 3, 4]);
+		assert_eq!(memory_from_str("109,\n\t204\n").unwrap(), vec![109, 204]);
+	}
+
+	#[test]
+	fn test_memory_from_str_errors() {
+		let error = |input: &str| memory_from_str(input).unwrap_err();
+		assert_eq!(error("1,2,\n3,x4,5"), ParseError { line: 2, column: 3, token: "x4".to_string() });
+		assert_eq!(error("1,,2"), ParseError { line: 1, column: 3, token: String::new() });
+		assert_eq!(error("1,2,\n  3 4,5"), ParseError { line: 2, column: 3, token: "3 4".to_string() });
+		// A missing comma isn't made up for by a newline
+		assert_eq!(error("1,2\n3"), ParseError { line: 1, column: 3, token: "2 3".to_string() });
+		assert_eq!(error("99999999999999999999"), ParseError { line: 1, column: 1, token: "99999999999999999999".to_string() });
+		assert_eq!(error("\n"), ParseError { line: 2, column: 1, token: String::new() });
+		assert_eq!(error("1,2,\n3,x4,5").to_string(), "line 2, column 3: \"x4\" is not a valid intcode value");
+	}
+
+	#[test]
+	fn test_from_str() {
+		let mut vm: IntCodeVM = "104,1125899906842624,99".parse().unwrap();
+		vm.run_all().unwrap();
+		assert_eq!(vm.pop_output(), Some(1125899906842624));
+	}
+
+	#[test]
+	fn test_errors() {
+		let error = |memory: Memory| IntCodeVM::new(memory).run_all().unwrap_err();
+		assert_eq!(error(vec![1, 0, 0, 0, 42]), VmError::UnknownOpcode { ip: 4, instruction: 42 });
+		assert_eq!(error(vec![301, 0, 0, 0, 99]), VmError::UnknownMode { ip: 0, instruction: 301, mode: 3 });
+		assert_eq!(error(vec![1, -1, 0, 0, 99]), VmError::BadAddress { ip: 0, instruction: 1, address: -1 });
+		assert_eq!(error(vec![109, -5, 201, 0, 0, 0, 99]), VmError::BadAddress { ip: 2, instruction: 201, address: -5 });
+		assert_eq!(error(vec![1101, 1, 1, 1 << 40, 99]), VmError::BadAddress { ip: 0, instruction: 1101, address: 1 << 40 });
+		assert_eq!(error(vec![11101, 1, 1, 0, 99]), VmError::ImmediateWrite { ip: 0, instruction: 11101 });
+		assert_eq!(error(vec![1105, 1, -3, 99]), VmError::BadAddress { ip: 0, instruction: 1105, address: -3 });
+		assert_eq!(error(vec![1102, NumType::MAX, 2, 0, 99]), VmError::Overflow { ip: 0, instruction: 1102 });
+		assert_eq!(error(vec![1105, 1, 100]), VmError::OutOfBounds { ip: 100 });
+		assert_eq!(error(vec![1, 0, 0, 0, 42]).to_string(), "ip 4: 42 has an unknown opcode 42");
+		assert_eq!(IntCodeVM::new_run_all_output(vec![3, 0, 99]).unwrap_err(), VmError::NeedsInput { ip: 0 });
+	}
+
+	#[test]
+	fn test_error_leaves_vm_alone() {
+		// The input isn't used up by a write that can't happen
+		let mut vm = IntCodeVM::new(vec![103, 0, 99]);
+		vm.push_input(5);
+		assert_eq!(vm.run_one_command().unwrap_err(), VmError::ImmediateWrite { ip: 0, instruction: 103 });
+		assert_eq!(vm.register, 0);
+		assert!(vm.has_input());
+		// Nor by one past the end of memory
+		let mut vm = IntCodeVM::new(vec![3, MAX_MEMORY as NumType, 99]);
+		vm.push_input(5);
+		let address = MAX_MEMORY as NumType;
+		assert_eq!(vm.run_one_command().unwrap_err(), VmError::BadAddress { ip: 0, instruction: 3, address });
+		assert_eq!(vm.register, 0);
+		assert!(vm.has_input());
+		// A jump that isn't taken doesn't care where it would have gone
+		let mut vm = IntCodeVM::new(vec![1106, 1, -1, 104, 7, 99]);
+		assert_eq!(vm.run_all().unwrap(), State::Stopped);
+		assert_eq!(vm.pop_output(), Some(7));
+	}
+
+	#[test]
+	fn test_assembled_factorial() {
+		let program = asm::assemble("
+			        arb #stack
+			        in rb+1                 ; n
+			        add #done, #0, rb+0     ; where fact returns to
+			        jt #1, #fact
+			done:   out rb+1
+			        hlt
+			; fact(n): rb+0 is the return address, rb+1 is n and gets the result
+			fact:   jf rb+1, #base
+			        arb #3
+			        add rb-2, #-1, rb+1
+			        add #back, #0, rb+0
+			        jt #1, #fact
+			back:   mul rb+1, rb-2, rb-2
+			        arb #-3
+			        jt #1, rb+0
+			base:   add #1, #0, rb+1
+			        jt #1, rb+0
+			stack:  db 0
+		").unwrap();
+		for (n, expected) in &[(0, 1), (1, 1), (5, 120), (10, 3628800)] {
+			let mut vm = IntCodeVM::new(program.clone());
+			vm.push_input(*n);
+			assert_eq!(vm.run_all().unwrap(), State::Stopped);
+			assert_eq!(vm.pop_output(), Some(*expected));
+		}
+	}
+
+	#[test]
+	fn test_engines_self_modifying() {
+		// The second time round the add at the start has been turned into a mul
+		let program = asm::assemble("
+			start:  add #2, #3, [result]
+			        out [result]
+			        jt [done], #end
+			        add #1, #0, [done]
+			        add #1101, #1, [start]
+			        jt #1, #start
+			end:    hlt
+			result: db 0
+			done:   db 0
+		").unwrap();
+		for &engine in &[Engine::Interpreter, Engine::Cached] {
+			let mut vm = IntCodeVM::new(program.clone());
+			vm.set_engine(engine);
+			assert_eq!(vm.run_all().unwrap(), State::Stopped);
+			assert_eq!(vm.output().take(), vec![5, 6]);
+		}
+
+		// Poking memory from outside is noticed too, and a bad mode is still only an error once it's used
+		let mut vm = IntCodeVM::new(vec![1106, 0, 3, 104, 7, 99]);
+		vm.run_all().unwrap();
+		for (address, &value) in [3106, 1, 6, 104, 8, 99].iter().enumerate() {
+			vm.poke(address, value);
+		}
+		vm.register = 0;
+		vm.state = State::Ready;
+		assert_eq!(vm.run_all().unwrap(), State::Stopped);
+		assert_eq!(vm.output().take(), vec![7, 8]);
+		vm.poke(0, 3105);
+		vm.register = 0;
+		vm.state = State::Ready;
+		assert_eq!(
+			vm.run_all().unwrap_err(),
+			VmError::UnknownMode { ip: 0, instruction: 3105, mode: 3 }
+		);
+	}
+
+	#[test]
+	fn test_snapshot_restore() {
+		let memory = memory_from_str(include_str!("../inputs/2019/Day9.txt")).unwrap();
+		let mut vm = IntCodeVM::new(memory);
+		vm.push_input(2);
+		for _ in 0..1000 {
+			vm.run_one_command().unwrap();
+		}
+		let snapshot = vm.snapshot();
+		let with_io = vm.snapshot_with_io();
+		vm.run_all().unwrap();
+		let answer = vm.pop_output();
+		assert!(answer.is_some());
+
+		// The input was already read, so going back without the queues still gets there
+		vm.restore(&snapshot);
+		vm.run_all().unwrap();
+		assert_eq!(vm.pop_output(), answer);
+
+		let mut vm = IntCodeVM::new(vec![3, 0, 4, 0, 99]);
+		vm.push_input(5);
+		let before = vm.snapshot_with_io();
+		vm.run_all().unwrap();
+		vm.restore(&before);
+		assert!(vm.has_input() && !vm.has_output());
+		let mut copy = IntCodeVM::from_snapshot(&before);
+		copy.run_all().unwrap();
+		assert_eq!(copy.pop_output(), Some(5));
+		assert_eq!(IntCodeVM::from_snapshot(&with_io).snapshot_with_io(), with_io);
+	}
+
+	#[test]
+	fn test_engines_agree() {
+		let memory = memory_from_str(include_str!("../inputs/2019/Day9.txt")).unwrap();
+		let outputs: Vec<_> = [Engine::Interpreter, Engine::Cached]
+			.iter()
+			.map(|&engine| {
+				let mut vm = IntCodeVM::new(memory.clone());
+				vm.set_engine(engine);
+				vm.push_input(1);
+				vm.run_all().unwrap();
+				vm.output().take()
+			})
+			.collect();
+		assert_eq!(outputs[0], outputs[1]);
+		assert_eq!(outputs[0].len(), 1);
+	}
+
 	#[test]
 	fn test_egs_d9_a(){
 		let memory_a = vec![1102,34915192,34915192,7,4,7,99,0];
 		let mut vma = IntCodeVM::new(memory_a);
-		vma.run_all();
+		vma.run_all().unwrap();
 		let output_a = vma.output().take();
 		assert_eq!(1,output_a.len());
 		assert_eq!(16, output_a[0].to_string().len());
 
 		let mut memory_b = vec![109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99];
 		let mut vmb = IntCodeVM::new(memory_b.clone());
-		vmb.run_all();
+		vmb.run_all().unwrap();
 		let mut output_b = vmb.output().take();
 		assert_eq!(memory_b.len(), output_b.len());
 		for _i in 0..16{
@@ -388,11 +1014,11 @@ mod tests {
 		vmad.push_input(6);
 		vmae.push_input(5);
 		loop {
-			vmaa.run_all();
-			vmab.run_all();
-			vmac.run_all();
-			vmad.run_all();
-			vmae.run_all();
+			vmaa.run_all().unwrap();
+			vmab.run_all().unwrap();
+			vmac.run_all().unwrap();
+			vmad.run_all().unwrap();
+			vmae.run_all().unwrap();
 
 			if vmaa.is_stopped()
 				&& vmab.is_stopped()
@@ -403,7 +1029,7 @@ mod tests {
 				break;
 			}
 		}
-		assert_eq!(139629729, vmae.output().borrow_mut().pop_front().unwrap());
+		assert_eq!(139629729, vmae.output().pop().unwrap());
 
 		let memory_b = vec![
 			3, 52, 1001, 52, -5, 52, 3, 53, 1, 52, 56, 54, 1007, 54, 5, 55, 1005, 55, 26, 1001, 54,
@@ -422,13 +1048,13 @@ mod tests {
 		vmbc.push_input(8);
 		vmbd.push_input(5);
 		vmbe.push_input(6);
-		vmba.run_all();
+		vmba.run_all().unwrap();
 		loop {
-			vmba.run_all();
-			vmbb.run_all();
-			vmbc.run_all();
-			vmbd.run_all();
-			vmbe.run_all();
+			vmba.run_all().unwrap();
+			vmbb.run_all().unwrap();
+			vmbc.run_all().unwrap();
+			vmbd.run_all().unwrap();
+			vmbe.run_all().unwrap();
 			if vmba.is_stopped()
 				&& vmbb.is_stopped()
 				&& vmbc.is_stopped()
@@ -438,7 +1064,7 @@ mod tests {
 				break;
 			}
 		}
-		assert_eq!(18216, vmbe.output().borrow_mut().pop_front().unwrap());
+		assert_eq!(18216, vmbe.output().pop().unwrap());
 	}
 
 	#[test]
@@ -457,17 +1083,17 @@ mod tests {
 		vmac.push_input(2);
 		vmad.push_input(1);
 		vmae.push_input(0);
-		vmaa.run_all();
+		vmaa.run_all().unwrap();
 		assert!(vmaa.is_stopped());
-		vmab.run_all();
+		vmab.run_all().unwrap();
 		assert!(vmab.is_stopped());
-		vmac.run_all();
+		vmac.run_all().unwrap();
 		assert!(vmac.is_stopped());
-		vmad.run_all();
+		vmad.run_all().unwrap();
 		assert!(vmad.is_stopped());
-		vmae.run_all();
+		vmae.run_all().unwrap();
 		assert!(vmae.is_stopped());
-		assert_eq!(43210, vmae.output().borrow_mut().pop_front().unwrap());
+		assert_eq!(43210, vmae.output().pop().unwrap());
 
 		let memory_b = vec![
 			3, 23, 3, 24, 1002, 24, 10, 24, 1002, 23, -1, 23, 101, 5, 23, 23, 1, 24, 23, 23, 4, 23,
@@ -484,17 +1110,17 @@ mod tests {
 		vmbc.push_input(2);
 		vmbd.push_input(3);
 		vmbe.push_input(4);
-		vmba.run_all();
+		vmba.run_all().unwrap();
 		assert!(vmba.is_stopped());
-		vmbb.run_all();
+		vmbb.run_all().unwrap();
 		assert!(vmbb.is_stopped());
-		vmbc.run_all();
+		vmbc.run_all().unwrap();
 		assert!(vmbc.is_stopped());
-		vmbd.run_all();
+		vmbd.run_all().unwrap();
 		assert!(vmbd.is_stopped());
-		vmbe.run_all();
+		vmbe.run_all().unwrap();
 		assert!(vmbe.is_stopped());
-		assert_eq!(54321, vmbe.output().borrow_mut().pop_front().unwrap());
+		assert_eq!(54321, vmbe.output().pop().unwrap());
 
 		let memory_c = vec![
 			3, 31, 3, 32, 1002, 32, 10, 32, 1001, 31, -2, 31, 1007, 31, 0, 33, 1002, 33, 7, 33, 1,
@@ -511,84 +1137,84 @@ mod tests {
 		vmcc.push_input(4);
 		vmcd.push_input(3);
 		vmce.push_input(2);
-		vmca.run_all();
+		vmca.run_all().unwrap();
 		assert!(vmca.is_stopped());
-		vmcb.run_all();
+		vmcb.run_all().unwrap();
 		assert!(vmcb.is_stopped());
-		vmcc.run_all();
+		vmcc.run_all().unwrap();
 		assert!(vmcc.is_stopped());
-		vmcd.run_all();
+		vmcd.run_all().unwrap();
 		assert!(vmcd.is_stopped());
-		vmce.run_all();
+		vmce.run_all().unwrap();
 		assert!(vmce.is_stopped());
-		assert_eq!(65210, vmce.output().borrow_mut().pop_front().unwrap());
+		assert_eq!(65210, vmce.output().pop().unwrap());
 	}
 
 	#[test]
 	fn test_egs_d5_b() {
 		let mut vmaa = IntCodeVM::new(vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8]);
 		vmaa.push_input(8);
-		vmaa.run_all();
-		assert_eq!(1, vmaa.output().borrow_mut().pop_front().unwrap());
+		vmaa.run_all().unwrap();
+		assert_eq!(1, vmaa.output().pop().unwrap());
 
 		let mut vmab = IntCodeVM::new(vec![3, 9, 8, 9, 10, 9, 4, 9, 99, -1, 8]);
 		vmab.push_input(81);
-		vmab.run_all();
-		assert_eq!(0, vmab.output().borrow_mut().pop_front().unwrap());
+		vmab.run_all().unwrap();
+		assert_eq!(0, vmab.output().pop().unwrap());
 
 		let mut vmba = IntCodeVM::new(vec![3, 9, 7, 9, 10, 9, 4, 9, 99, -1, 8]);
 		vmba.push_input(88);
-		vmba.run_all();
-		assert_eq!(0, vmba.output().borrow_mut().pop_front().unwrap());
+		vmba.run_all().unwrap();
+		assert_eq!(0, vmba.output().pop().unwrap());
 
 		let mut vmbb = IntCodeVM::new(vec![3, 9, 7, 9, 10, 9, 4, 9, 99, -1, 8]);
 		vmbb.push_input(3);
-		vmbb.run_all();
-		assert_eq!(1, vmbb.output().borrow_mut().pop_front().unwrap());
+		vmbb.run_all().unwrap();
+		assert_eq!(1, vmbb.output().pop().unwrap());
 
 		let mut vmca = IntCodeVM::new(vec![3, 3, 1108, -1, 8, 3, 4, 3, 99]);
 		vmca.push_input(8);
-		vmca.run_all();
-		assert_eq!(1, vmca.output().borrow_mut().pop_front().unwrap());
+		vmca.run_all().unwrap();
+		assert_eq!(1, vmca.output().pop().unwrap());
 
 		let mut vmcb = IntCodeVM::new(vec![3, 3, 1108, -1, 8, 3, 4, 3, 99]);
 		vmcb.push_input(81);
-		vmcb.run_all();
-		assert_eq!(0, vmcb.output().borrow_mut().pop_front().unwrap());
+		vmcb.run_all().unwrap();
+		assert_eq!(0, vmcb.output().pop().unwrap());
 
 		let mut vmda = IntCodeVM::new(vec![3, 3, 1107, -1, 8, 3, 4, 3, 99]);
 		vmda.push_input(88);
-		vmda.run_all();
-		assert_eq!(0, vmda.output().borrow_mut().pop_front().unwrap());
+		vmda.run_all().unwrap();
+		assert_eq!(0, vmda.output().pop().unwrap());
 
 		let mut vmdb = IntCodeVM::new(vec![3, 3, 1107, -1, 8, 3, 4, 3, 99]);
 		vmdb.push_input(3);
-		vmdb.run_all();
-		assert_eq!(1, vmdb.output().borrow_mut().pop_front().unwrap());
+		vmdb.run_all().unwrap();
+		assert_eq!(1, vmdb.output().pop().unwrap());
 
 		let mut vmea = IntCodeVM::new(vec![
 			3, 12, 6, 12, 15, 1, 13, 14, 13, 4, 13, 99, -1, 0, 1, 9,
 		]);
 		vmea.push_input(0);
-		vmea.run_all();
-		assert_eq!(0, vmea.output().borrow_mut().pop_front().unwrap());
+		vmea.run_all().unwrap();
+		assert_eq!(0, vmea.output().pop().unwrap());
 
 		let mut vmeb = IntCodeVM::new(vec![
 			3, 12, 6, 12, 15, 1, 13, 14, 13, 4, 13, 99, -1, 0, 1, 9,
 		]);
 		vmeb.push_input(81);
-		vmeb.run_all();
-		assert_eq!(1, vmeb.output().borrow_mut().pop_front().unwrap());
+		vmeb.run_all().unwrap();
+		assert_eq!(1, vmeb.output().pop().unwrap());
 
 		let mut vmfa = IntCodeVM::new(vec![3, 3, 1105, -1, 9, 1101, 0, 0, 12, 4, 12, 99, 1]);
 		vmfa.push_input(0);
-		vmfa.run_all();
-		assert_eq!(0, vmfa.output().borrow_mut().pop_front().unwrap());
+		vmfa.run_all().unwrap();
+		assert_eq!(0, vmfa.output().pop().unwrap());
 
 		let mut vmfb = IntCodeVM::new(vec![3, 3, 1105, -1, 9, 1101, 0, 0, 12, 4, 12, 99, 1]);
 		vmfb.push_input(81);
-		vmfb.run_all();
-		assert_eq!(1, vmfb.output().borrow_mut().pop_front().unwrap());
+		vmfb.run_all().unwrap();
+		assert_eq!(1, vmfb.output().pop().unwrap());
 
 		let mut vmga = IntCodeVM::new(vec![
 			3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0,
@@ -596,8 +1222,8 @@ mod tests {
 			20, 1105, 1, 46, 98, 99,
 		]);
 		vmga.push_input(7);
-		vmga.run_all();
-		assert_eq!(999, vmga.output().borrow_mut().pop_front().unwrap());
+		vmga.run_all().unwrap();
+		assert_eq!(999, vmga.output().pop().unwrap());
 
 		let mut vmgb = IntCodeVM::new(vec![
 			3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0,
@@ -605,8 +1231,8 @@ mod tests {
 			20, 1105, 1, 46, 98, 99,
 		]);
 		vmgb.push_input(8);
-		vmgb.run_all();
-		assert_eq!(1000, vmgb.output().borrow_mut().pop_front().unwrap());
+		vmgb.run_all().unwrap();
+		assert_eq!(1000, vmgb.output().pop().unwrap());
 
 		let mut vmgc = IntCodeVM::new(vec![
 			3, 21, 1008, 21, 8, 20, 1005, 20, 22, 107, 8, 21, 20, 1006, 20, 31, 1106, 0, 36, 98, 0,
@@ -614,64 +1240,64 @@ mod tests {
 			20, 1105, 1, 46, 98, 99,
 		]);
 		vmgc.push_input(9);
-		vmgc.run_all();
-		assert_eq!(1001, vmgc.output().borrow_mut().pop_front().unwrap());
+		vmgc.run_all().unwrap();
+		assert_eq!(1001, vmgc.output().pop().unwrap());
 	}
 
 	#[test]
 	fn test_egs_d5_a() {
 		let mut vma = IntCodeVM::new(vec![3, 0, 4, 0, 99]);
 		vma.push_input(8);
-		vma.run_all();
+		vma.run_all().unwrap();
 		let outa = vma.output();
 		assert_eq!(State::Stopped, vma.state);
-		assert_eq!(1, outa.borrow().len());
-		assert_eq!(8, outa.borrow_mut().pop_front().unwrap());
+		assert_eq!(1, outa.len());
+		assert_eq!(8, outa.pop().unwrap());
 
 		let mut vmb = IntCodeVM::new(vec![3, 0, 4, 0, 99]);
-		vmb.run_all();
+		vmb.run_all().unwrap();
 		let outba = vmb.output();
 		assert_eq!(State::Reading, vmb.state);
-		assert_eq!(0, outba.borrow().len());
+		assert_eq!(0, outba.len());
 		vmb.push_input(6);
 		let outbb = vmb.output();
 		assert_eq!(State::Ready, vmb.state);
-		assert_eq!(0, outbb.borrow().len());
-		vmb.run_all();
+		assert_eq!(0, outbb.len());
+		vmb.run_all().unwrap();
 		let outbc = vmb.output();
 		assert_eq!(State::Stopped, vmb.state);
-		assert_eq!(1, outbc.borrow().len());
-		assert_eq!(6, outbc.borrow_mut().pop_front().unwrap());
+		assert_eq!(1, outbc.len());
+		assert_eq!(6, outbc.pop().unwrap());
 
 		let mut vmc = IntCodeVM::new(vec![1002, 4, 3, 4, 33]);
-		vmc.run_all();
+		vmc.run_all().unwrap();
 		let post_c: Memory = vec![1002, 4, 3, 4, 99];
 		assert_eq!(vmc.memory, post_c);
 	}
 	#[test]
 	fn test_egs_d2() {
 		let mut vma = IntCodeVM::new(vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50]);
-		vma.run_all();
+		vma.run_all().unwrap();
 		let post_a: Memory = vec![3500, 9, 10, 70, 2, 3, 11, 0, 99, 30, 40, 50];
 		assert_eq!(vma.memory, post_a);
 
 		let mut vmb = IntCodeVM::new(vec![1, 0, 0, 0, 99]);
-		vmb.run_all();
+		vmb.run_all().unwrap();
 		let post_b: Memory = vec![2, 0, 0, 0, 99];
 		assert_eq!(vmb.memory, post_b);
 
 		let mut vmc = IntCodeVM::new(vec![2, 3, 0, 3, 99]);
-		vmc.run_all();
+		vmc.run_all().unwrap();
 		let post_c: Memory = vec![2, 3, 0, 6, 99];
 		assert_eq!(vmc.memory, post_c);
 
 		let mut vmd = IntCodeVM::new(vec![1, 1, 1, 4, 99, 5, 6, 0, 99]);
-		vmd.run_all();
+		vmd.run_all().unwrap();
 		let post_d: Memory = vec![30, 1, 1, 4, 2, 5, 6, 0, 99];
 		assert_eq!(vmd.memory, post_d);
 
 		let mut vme = IntCodeVM::new(vec![2, 4, 4, 5, 99, 0]);
-		vme.run_all();
+		vme.run_all().unwrap();
 		let post_e: Memory = vec![2, 4, 4, 5, 99, 9801];
 		assert_eq!(vme.memory, post_e);
 	}
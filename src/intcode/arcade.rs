@@ -0,0 +1,407 @@
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use super::{IntCodeVM, Memory, NumType, VmError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tile {
+	Empty,
+	Wall,
+	Block,
+	Paddle,
+	Ball,
+}
+
+impl Tile {
+	pub fn from_id(id: NumType) -> Option<Tile> {
+		match id {
+			0 => Some(Tile::Empty),
+			1 => Some(Tile::Wall),
+			2 => Some(Tile::Block),
+			3 => Some(Tile::Paddle),
+			4 => Some(Tile::Ball),
+			_ => None,
+		}
+	}
+
+	pub fn to_char(self) -> char {
+		match self {
+			Tile::Empty => ' ',
+			Tile::Wall => '#',
+			Tile::Block => '=',
+			Tile::Paddle => '-',
+			Tile::Ball => 'o',
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArcadeError {
+	Vm(VmError),
+	/// Output has to come in (x, y, tile) threes
+	PartialOutput(usize),
+	BadTile { x: NumType, y: NumType, id: NumType },
+}
+
+impl fmt::Display for ArcadeError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			ArcadeError::Vm(error) => write!(f, "{}", error),
+			ArcadeError::PartialOutput(left) => write!(f, "{} value(s) left over that aren't a whole tile", left),
+			ArcadeError::BadTile { x, y, id } => write!(f, "{} at ({}, {}) isn't a tile", id, x, y),
+		}
+	}
+}
+
+impl Error for ArcadeError {}
+
+impl From<VmError> for ArcadeError {
+	fn from(error: VmError) -> ArcadeError {
+		ArcadeError::Vm(error)
+	}
+}
+
+/// What the cabinet's showing. Output is drawn as (x, y, tile) threes, except (-1, 0, n) which puts n on the
+/// score display
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Screen {
+	tiles: HashMap<(NumType, NumType), Tile>,
+	score: NumType,
+}
+
+impl Screen {
+	pub fn new() -> Screen {
+		Screen::default()
+	}
+
+	pub fn draw<I: IntoIterator<Item = NumType>>(&mut self, output: I) -> Result<(), ArcadeError> {
+		let values: Vec<NumType> = output.into_iter().collect();
+		let left = values.len() % 3;
+		if left > 0 {
+			return Err(ArcadeError::PartialOutput(left));
+		}
+		for triple in values.chunks(3) {
+			let (x, y, id) = (triple[0], triple[1], triple[2]);
+			if (x, y) == (-1, 0) {
+				self.score = id;
+			} else {
+				let tile = Tile::from_id(id).ok_or(ArcadeError::BadTile { x, y, id })?;
+				self.tiles.insert((x, y), tile);
+			}
+		}
+		Ok(())
+	}
+
+	pub fn score(&self) -> NumType {
+		self.score
+	}
+
+	pub fn tile(&self, x: NumType, y: NumType) -> Tile {
+		*self.tiles.get(&(x, y)).unwrap_or(&Tile::Empty)
+	}
+
+	/// Where a tile is, if it's on screen. Meant for the ball and paddle, there's only one of each
+	pub fn find(&self, tile: Tile) -> Option<(NumType, NumType)> {
+		self.tiles.iter().find(|(_, &t)| t == tile).map(|(&position, _)| position)
+	}
+
+	pub fn count(&self, tile: Tile) -> usize {
+		self.tiles.values().filter(|&&t| t == tile).count()
+	}
+
+	/// The screen as text, with the score underneath
+	pub fn render(&self) -> String {
+		let mut text = String::new();
+		if let (Some(width), Some(height)) =
+			(self.tiles.keys().map(|&(x, _)| x).max(), self.tiles.keys().map(|&(_, y)| y).max())
+		{
+			for y in 0..=height {
+				let line: String = (0..=width).map(|x| self.tile(x, y).to_char()).collect();
+				text += line.trim_end();
+				text.push('\n');
+			}
+		}
+		text += &format!("Score: {}\n", self.score);
+		text
+	}
+}
+
+/// Which way to move the joystick: -1 is left, 0 leaves it in the middle and 1 is right
+pub trait Policy {
+	/// `None` stops the game where it is
+	fn next_move(&mut self, screen: &Screen) -> Option<NumType>;
+}
+
+impl<P: Policy + ?Sized> Policy for Box<P> {
+	fn next_move(&mut self, screen: &Screen) -> Option<NumType> {
+		(**self).next_move(screen)
+	}
+}
+
+/// Keep the paddle under the ball
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FollowBall;
+
+impl Policy for FollowBall {
+	fn next_move(&mut self, screen: &Screen) -> Option<NumType> {
+		match (screen.find(Tile::Ball), screen.find(Tile::Paddle)) {
+			(Some((ball, _)), Some((paddle, _))) => Some((ball - paddle).signum()),
+			_ => Some(0),
+		}
+	}
+}
+
+/// Moves written down ahead of time, one character each: `<` left, `.` stay, `>` right. Anything else is skipped
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Script {
+	moves: VecDeque<NumType>,
+}
+
+impl Script {
+	pub fn parse(text: &str) -> Script {
+		Script { moves: text.chars().filter_map(key_move).collect() }
+	}
+
+	/// Moves as a script, so a game can be played again
+	pub fn write(moves: &[NumType]) -> String {
+		moves.iter().map(|&m| match m.signum() {
+			-1 => '<',
+			0 => '.',
+			_ => '>',
+		}).collect()
+	}
+}
+
+impl Policy for Script {
+	fn next_move(&mut self, _: &Screen) -> Option<NumType> {
+		self.moves.pop_front()
+	}
+}
+
+/// A player at the keyboard: moves are typed in the script format and read a line at a time as they're needed
+pub struct Keyboard<R> {
+	reader: R,
+	moves: VecDeque<NumType>,
+}
+
+impl<R: BufRead> Keyboard<R> {
+	pub fn new(reader: R) -> Keyboard<R> {
+		Keyboard { reader, moves: VecDeque::new() }
+	}
+}
+
+impl<R: BufRead> Policy for Keyboard<R> {
+	fn next_move(&mut self, _: &Screen) -> Option<NumType> {
+		while self.moves.is_empty() {
+			let mut line = String::new();
+			if self.reader.read_line(&mut line).ok()? == 0 {
+				return None;
+			}
+			// Just pressing enter leaves the joystick alone
+			self.moves.extend(line.chars().filter_map(key_move));
+			if self.moves.is_empty() {
+				self.moves.push_back(0);
+			}
+		}
+		self.moves.pop_front()
+	}
+}
+
+fn key_move(key: char) -> Option<NumType> {
+	match key {
+		'<' => Some(-1),
+		'.' => Some(0),
+		'>' => Some(1),
+		_ => None,
+	}
+}
+
+/// Shown each frame, whenever the game's waiting for a move and once more at the end
+pub trait Renderer {
+	fn frame(&mut self, screen: &Screen);
+}
+
+/// Show each frame on both
+impl<A: Renderer, B: Renderer> Renderer for (A, B) {
+	fn frame(&mut self, screen: &Screen) {
+		self.0.frame(screen);
+		self.1.frame(screen);
+	}
+}
+
+impl<R: Renderer> Renderer for Option<R> {
+	fn frame(&mut self, screen: &Screen) {
+		if let Some(renderer) = self {
+			renderer.frame(screen);
+		}
+	}
+}
+
+/// Shows nothing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Headless;
+
+impl Renderer for Headless {
+	fn frame(&mut self, _: &Screen) {}
+}
+
+/// Redraws the terminal each frame, waiting `delay` so it can be watched
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Terminal {
+	pub delay: Duration,
+}
+
+impl Renderer for Terminal {
+	fn frame(&mut self, screen: &Screen) {
+		print!("\x1b[2J\x1b[H{}", screen.render());
+		let _ = io::stdout().flush();
+		thread::sleep(self.delay);
+	}
+}
+
+/// Writes every frame one after another, numbered. Like `TraceFile`, the first error is kept for `finish`
+#[derive(Debug)]
+pub struct FrameLog<W: Write> {
+	writer: W,
+	frames: usize,
+	error: Option<io::Error>,
+}
+
+impl FrameLog<BufWriter<File>> {
+	pub fn create(path: &Path) -> io::Result<FrameLog<BufWriter<File>>> {
+		Ok(FrameLog::new(BufWriter::new(File::create(path)?)))
+	}
+}
+
+impl<W: Write> FrameLog<W> {
+	pub fn new(writer: W) -> FrameLog<W> {
+		FrameLog { writer, frames: 0, error: None }
+	}
+
+	pub fn finish(mut self) -> io::Result<W> {
+		match self.error.take() {
+			Some(error) => Err(error),
+			None => self.writer.flush().map(|_| self.writer),
+		}
+	}
+}
+
+impl<W: Write> Renderer for FrameLog<W> {
+	fn frame(&mut self, screen: &Screen) {
+		self.frames += 1;
+		if self.error.is_none() {
+			if let Err(error) = write!(self.writer, "Frame {}\n{}\n", self.frames, screen.render()) {
+				self.error = Some(error);
+			}
+		}
+	}
+}
+
+/// How a game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ending {
+	/// The program halted: every block's gone, or the ball got past the paddle
+	GameOver,
+	/// The policy ran out of moves first
+	OutOfMoves,
+}
+
+/// Day 13's cabinet: the game program, its screen, and every move made so far
+#[derive(Debug, Clone)]
+pub struct Arcade {
+	vm: IntCodeVM,
+	screen: Screen,
+	moves: Vec<NumType>,
+}
+
+impl Arcade {
+	pub fn new(memory: Memory) -> Arcade {
+		Arcade { vm: IntCodeVM::new(memory), screen: Screen::new(), moves: Vec::new() }
+	}
+
+	/// Two quarters in address 0 means it can be played rather than just showing the screen
+	pub fn free_play(mut memory: Memory) -> Arcade {
+		if memory.is_empty() {
+			memory.push(0);
+		}
+		memory[0] = 2;
+		Arcade::new(memory)
+	}
+
+	pub fn screen(&self) -> &Screen {
+		&self.screen
+	}
+
+	pub fn moves(&self) -> &[NumType] {
+		&self.moves
+	}
+
+	/// Run until it wants the joystick or halts, drawing what it output
+	pub fn run(&mut self) -> Result<bool, ArcadeError> {
+		self.vm.run_all()?;
+		self.screen.draw(self.vm.output().take())?;
+		Ok(self.vm.is_stopped())
+	}
+
+	pub fn play<P: Policy, R: Renderer>(&mut self, policy: &mut P, renderer: &mut R) -> Result<Ending, ArcadeError> {
+		loop {
+			let halted = self.run()?;
+			renderer.frame(&self.screen);
+			if halted {
+				return Ok(Ending::GameOver);
+			}
+			match policy.next_move(&self.screen) {
+				Some(joystick) => {
+					self.moves.push(joystick);
+					self.vm.push_input(joystick);
+				}
+				None => return Ok(Ending::OutOfMoves),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::intcode::memory_from_str;
+
+	#[test]
+	fn test_screen() {
+		let mut screen = Screen::new();
+		screen.draw(vec![0, 0, 1, 1, 0, 2, 2, 1, 4, 1, 2, 3, -1, 0, 12]).unwrap();
+		assert_eq!(screen.render(), "#=\n  o\n -\nScore: 12\n");
+		assert_eq!(screen.find(Tile::Ball), Some((2, 1)));
+		assert_eq!(screen.draw(vec![0, 0]), Err(ArcadeError::PartialOutput(2)));
+		assert_eq!(screen.draw(vec![5, 5, 9]), Err(ArcadeError::BadTile { x: 5, y: 5, id: 9 }));
+	}
+
+	#[test]
+	fn test_replay_and_frames() {
+		let memory = memory_from_str(include_str!("../../inputs/2019/Day13.txt")).unwrap();
+		let mut arcade = Arcade::free_play(memory.clone());
+		assert_eq!(arcade.play(&mut FollowBall, &mut Headless), Ok(Ending::GameOver));
+		assert_eq!(arcade.screen().count(Tile::Block), 0);
+		let score = arcade.screen().score();
+
+		// The same moves again from a script give the same game, and a frame log shows every one of them
+		let mut script = Script::parse(&Script::write(arcade.moves()));
+		let mut replay = Arcade::free_play(memory.clone());
+		let mut log = FrameLog::new(Vec::new());
+		assert_eq!(replay.play(&mut script, &mut log), Ok(Ending::GameOver));
+		assert_eq!(replay.screen().score(), score);
+		let log = String::from_utf8(log.finish().unwrap()).unwrap();
+		assert_eq!(log.matches("Frame ").count(), arcade.moves().len() + 1);
+
+		let mut keyboard = Keyboard::new(&b"<\n\n>>\n"[..]);
+		let mut game = Arcade::free_play(memory);
+		assert_eq!(game.play(&mut keyboard, &mut Headless), Ok(Ending::OutOfMoves));
+		assert_eq!(game.moves(), &[-1, 0, 1, 1]);
+	}
+}
@@ -0,0 +1,121 @@
+use super::{IntCodeVM, Memory, NumType, VmError};
+
+/// What a program printed before it stopped to wait for input, or halted
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Response {
+	pub text: String,
+	/// Anything out of ASCII's range, which is where these programs put their answers
+	pub values: Vec<NumType>,
+	pub halted: bool,
+}
+
+/// An `IntCodeVM` for the programs that talk in text: commands go in as lines and what comes back is a string,
+/// with any number too big to be a character kept to one side
+#[derive(Debug, Clone)]
+pub struct AsciiVm {
+	vm: IntCodeVM,
+}
+
+impl AsciiVm {
+	pub fn new(memory: Memory) -> AsciiVm {
+		AsciiVm { vm: IntCodeVM::new(memory) }
+	}
+
+	pub fn from_vm(vm: IntCodeVM) -> AsciiVm {
+		AsciiVm { vm }
+	}
+
+	pub fn vm(&self) -> &IntCodeVM {
+		&self.vm
+	}
+
+	pub fn vm_mut(&mut self) -> &mut IntCodeVM {
+		&mut self.vm
+	}
+
+	pub fn into_vm(self) -> IntCodeVM {
+		self.vm
+	}
+
+	/// Queue a line of input, the newline's added
+	pub fn send_line(&mut self, line: &str) {
+		line.chars().chain(Some('\n')).for_each(|c| self.vm.push_input(c as NumType));
+	}
+
+	pub fn send_lines<I: IntoIterator<Item = S>, S: AsRef<str>>(&mut self, lines: I) {
+		lines.into_iter().for_each(|line| self.send_line(line.as_ref()));
+	}
+
+	/// Run until it wants input it hasn't got, or halts, and say what it printed on the way
+	pub fn read_until_prompt(&mut self) -> Result<Response, VmError> {
+		self.vm.run_all()?;
+		Ok(self.response())
+	}
+
+	/// The same, but giving up after `budget` instructions, for programs that might never get back to asking
+	pub fn read_until_prompt_within(&mut self, budget: usize) -> Result<Option<Response>, VmError> {
+		for _ in 0..budget {
+			if self.vm.is_stopped() || (self.vm.is_waiting() && !self.vm.has_input()) {
+				return Ok(Some(self.response()));
+			}
+			self.vm.run_one_command()?;
+		}
+		Ok(None)
+	}
+
+	/// Send some lines and see what comes back
+	pub fn run_lines<I: IntoIterator<Item = S>, S: AsRef<str>>(&mut self, lines: I) -> Result<Response, VmError> {
+		self.send_lines(lines);
+		self.read_until_prompt()
+	}
+
+	fn response(&mut self) -> Response {
+		let mut response = Response { halted: self.vm.is_stopped(), ..Response::default() };
+		for value in self.vm.output().take() {
+			if (0..128).contains(&value) {
+				response.text.push(value as u8 as char);
+			} else {
+				response.values.push(value);
+			}
+		}
+		response
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::intcode::asm::assemble;
+
+	#[test]
+	fn test_prompt_and_reply() {
+		// Ask for a line, then say ok and how long it was
+		let memory = assemble(
+			"
+			        out #63
+			        out #10
+			read:   in [char]
+			        eq [char], #10, [done]
+			        jt [done], #reply
+			        add [length], #1, [length]
+			        jt #1, #read
+			reply:  out #111
+			        out #107
+			        out #10
+			        mul [length], #1000, [length]
+			        out [length]
+			        hlt
+			char:   db 0
+			done:   db 0
+			length: db 0
+			",
+		)
+		.unwrap();
+		let mut vm = AsciiVm::new(memory);
+		assert_eq!(vm.read_until_prompt().unwrap(), Response { text: "?\n".to_string(), values: vec![], halted: false });
+		let response = vm.run_lines(["hi"]).unwrap();
+		assert_eq!(response.text, "ok\n");
+		assert_eq!(response.values, vec![2000]);
+		assert!(response.halted);
+	}
+}
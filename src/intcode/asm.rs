@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use super::{Memory, Mode, NumType, Opcode};
+
+/// Where some assembly stopped making sense, lines count from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsmError {
+	pub line: usize,
+	pub message: String,
+}
+
+impl fmt::Display for AsmError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "line {}: {}", self.line, self.message)
+	}
+}
+
+impl Error for AsmError {}
+
+/// A number, or a label with an optional offset like `loop+2`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+	Number(NumType),
+	Label(String, NumType),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Item {
+	Instruction(Opcode, Vec<(Mode, Expr)>),
+	Data(Vec<Expr>),
+}
+
+impl Item {
+	fn size(&self) -> usize {
+		match self {
+			Item::Instruction(_, operands) => operands.len() + 1,
+			Item::Data(values) => values.len(),
+		}
+	}
+}
+
+/// Turn assembly into a program. It's what `disasm::listing` writes, so a listing assembles back to
+/// the same memory:
+///
+/// ```text
+/// start:  in [count]          ; labels end in a colon
+/// loop:   out [count]
+///         add [count], #-1, [count]
+///         jt [count], #loop   ; [pos], #imm and rb+N (or rb-N) pick the mode
+///         hlt
+/// count:  db 0
+/// hello:  db "Hi\n", 0        ; strings are their characters' codes
+/// ```
+///
+/// A number in front of a colon is the address the line should be at, it's checked rather than used
+pub fn assemble(source: &str) -> Result<Memory, AsmError> {
+	let mut items = Vec::new();
+	let mut labels = HashMap::new();
+	let mut address = 0;
+	for (number, line) in source.lines().enumerate() {
+		let error = |message: String| AsmError { line: number + 1, message };
+		let mut rest = strip_comment(line).trim();
+		while let Some((name, after)) = prefix(rest) {
+			if let Ok(expected) = name.parse::<usize>() {
+				if expected != address {
+					return Err(error(format!("this is at address {}, not {}", address, expected)));
+				}
+			} else if labels.insert(name.to_string(), address).is_some() {
+				return Err(error(format!("{} is already a label", name)));
+			}
+			rest = after.trim_start();
+		}
+		if rest.is_empty() {
+			continue;
+		}
+		let item = parse_item(rest).map_err(error)?;
+		address += item.size();
+		items.push((number + 1, item));
+	}
+
+	let mut memory = Vec::with_capacity(address);
+	for (line, item) in items {
+		let resolve = |expr: &Expr| match expr {
+			Expr::Number(value) => Ok(*value),
+			Expr::Label(name, offset) => labels
+				.get(name)
+				.map(|&address| address as NumType + offset)
+				.ok_or_else(|| AsmError { line, message: format!("{} isn't a label", name) }),
+		};
+		match item {
+			Item::Instruction(opcode, operands) => {
+				let modes = operands.iter().rev().fold(0, |modes, (mode, _)| modes * 10 + *mode as NumType);
+				memory.push(modes * 100 + opcode as NumType);
+				for (_, expr) in &operands {
+					memory.push(resolve(expr)?);
+				}
+			}
+			Item::Data(values) => {
+				for value in &values {
+					memory.push(resolve(value)?);
+				}
+			}
+		}
+	}
+	Ok(memory)
+}
+
+/// Everything before a `;` that isn't in a string
+fn strip_comment(line: &str) -> &str {
+	let mut in_string = false;
+	let mut escaped = false;
+	for (i, c) in line.char_indices() {
+		match c {
+			_ if escaped => escaped = false,
+			'\\' if in_string => escaped = true,
+			'"' => in_string = !in_string,
+			';' if !in_string => return &line[..i],
+			_ => {}
+		}
+	}
+	line
+}
+
+/// A label or address at the start of a line, and what's after its colon
+fn prefix(line: &str) -> Option<(&str, &str)> {
+	let end = line.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(line.len());
+	let (name, rest) = line.split_at(end);
+	let rest = rest.trim_start().strip_prefix(':')?;
+	if name.is_empty() {
+		return None;
+	}
+	Some((name, rest))
+}
+
+fn parse_item(line: &str) -> Result<Item, String> {
+	let (mnemonic, rest) = line.split_at(line.find(char::is_whitespace).unwrap_or(line.len()));
+	let operands = split_operands(rest)?;
+	if mnemonic == "db" {
+		let mut values = Vec::new();
+		for operand in operands {
+			if operand.starts_with('"') {
+				values.extend(parse_string(operand)?.chars().map(|c| Expr::Number(c as NumType)));
+			} else {
+				values.push(parse_expr(operand)?);
+			}
+		}
+		if values.is_empty() {
+			return Err("db needs at least one value".to_string());
+		}
+		return Ok(Item::Data(values));
+	}
+	let opcode = Opcode::from_mnemonic(mnemonic).ok_or_else(|| format!("{:?} isn't an instruction", mnemonic))?;
+	if operands.len() != opcode.params() {
+		return Err(format!("{} takes {} operands, not {}", mnemonic, opcode.params(), operands.len()));
+	}
+	let operands = operands.into_iter().map(parse_operand).collect::<Result<Vec<_>, _>>()?;
+	if let Some(i) = opcode.writes() {
+		if operands[i].0 == Mode::Immediate {
+			return Err(format!("{} can't write to an immediate", mnemonic));
+		}
+	}
+	Ok(Item::Instruction(opcode, operands))
+}
+
+/// Split on the commas that aren't in strings
+fn split_operands(text: &str) -> Result<Vec<&str>, String> {
+	if text.trim().is_empty() {
+		return Ok(Vec::new());
+	}
+	let mut operands = Vec::new();
+	let mut start = 0;
+	let (mut in_string, mut escaped) = (false, false);
+	for (i, c) in text.char_indices() {
+		match c {
+			_ if escaped => escaped = false,
+			'\\' if in_string => escaped = true,
+			'"' => in_string = !in_string,
+			',' if !in_string => {
+				operands.push(text[start..i].trim());
+				start = i + 1;
+			}
+			_ => {}
+		}
+	}
+	operands.push(text[start..].trim());
+	if operands.iter().any(|operand| operand.is_empty()) {
+		return Err("there's an empty operand".to_string());
+	}
+	Ok(operands)
+}
+
+fn parse_operand(operand: &str) -> Result<(Mode, Expr), String> {
+	if let Some(value) = operand.strip_prefix('#') {
+		Ok((Mode::Immediate, parse_expr(value)?))
+	} else if let Some(inner) = operand.strip_prefix('[').and_then(|o| o.strip_suffix(']')) {
+		Ok((Mode::Position, parse_expr(inner)?))
+	} else if let Some(offset) = operand.strip_prefix("rb") {
+		let offset = offset.trim();
+		if offset.is_empty() {
+			Ok((Mode::Relative, Expr::Number(0)))
+		} else if offset.starts_with('+') || offset.starts_with('-') {
+			// rb-3 is rb + -3
+			Ok((Mode::Relative, parse_expr(offset.trim_start_matches('+'))?))
+		} else {
+			Err(format!("{:?} should be rb+N or rb-N", operand))
+		}
+	} else {
+		Err(format!("{:?} needs a mode: [pos], #imm or rb+N", operand))
+	}
+}
+
+fn parse_expr(text: &str) -> Result<Expr, String> {
+	let text = text.trim();
+	if let Ok(value) = text.parse() {
+		return Ok(Expr::Number(value));
+	}
+	let split = text.find(['+', '-']).unwrap_or(text.len());
+	let (name, offset) = text.split_at(split);
+	let name = name.trim();
+	let valid_name = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+		&& name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+	if !valid_name {
+		return Err(format!("{:?} isn't a number or a label", text));
+	}
+	let offset = match offset.trim_start_matches('+').replace(' ', "") {
+		offset if offset.is_empty() => 0,
+		offset => offset.parse().map_err(|_| format!("{:?} isn't a label plus or minus a number", text))?,
+	};
+	Ok(Expr::Label(name.to_string(), offset))
+}
+
+fn parse_string(text: &str) -> Result<String, String> {
+	let inner = text
+		.strip_prefix('"')
+		.and_then(|t| t.strip_suffix('"'))
+		.ok_or_else(|| format!("{} isn't a finished string", text))?;
+	let mut string = String::new();
+	let mut chars = inner.chars();
+	while let Some(c) = chars.next() {
+		if c != '\\' {
+			string.push(c);
+			continue;
+		}
+		match chars.next() {
+			Some('n') => string.push('\n'),
+			Some('t') => string.push('\t'),
+			Some('"') => string.push('"'),
+			Some('\\') => string.push('\\'),
+			other => return Err(format!("\\{} isn't an escape", other.map(String::from).unwrap_or_default())),
+		}
+	}
+	Ok(string)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::intcode::disasm::listing;
+	use crate::intcode::memory_from_str;
+
+	#[test]
+	fn test_assemble() {
+		let source = "
+			start:  in [count]          ; how many to count down from
+			loop:   out [count]
+			        add [count], #-1, [count]
+			        jt [count], #loop
+			        arb #end
+			        out rb-1
+			        hlt
+			count:  db 0
+			end:    db \"Hi\\n\", -1, end+1
+		";
+		assert_eq!(
+			assemble(source).unwrap(),
+			vec![3, 16, 4, 16, 1001, 16, -1, 16, 1005, 16, 2, 109, 17, 204, -1, 99, 0, 72, 105, 10, -1, 18]
+		);
+	}
+
+	#[test]
+	fn test_errors() {
+		let error = |source: &str| assemble(source).unwrap_err();
+		assert_eq!(error("hlt\nfoo [1]").to_string(), "line 2: \"foo\" isn't an instruction");
+		assert_eq!(error("add #1, #2, #3").line, 1);
+		assert_eq!(error("add #1, #2").line, 1);
+		assert_eq!(error("out 5").line, 1);
+		assert_eq!(error("jt #1, #nowhere").message, "nowhere isn't a label");
+		assert_eq!(error("a: hlt\na: hlt").line, 2);
+		assert_eq!(error("hlt\n0: hlt").message, "this is at address 1, not 0");
+		assert_eq!(error("db \"unfinished").line, 1);
+	}
+
+	#[test]
+	fn test_semicolon_in_string() {
+		assert_eq!(assemble("db \";\", 1 ; a comment").unwrap(), vec![59, 1]);
+	}
+
+	#[test]
+	fn test_round_trip() {
+		for program in &[
+			include_str!("../../inputs/2019/Day9.txt"),
+			include_str!("../../inputs/2019/Day21.txt"),
+			include_str!("../../inputs/2019/Day25.txt"),
+		] {
+			let memory = memory_from_str(program).unwrap();
+			assert_eq!(assemble(&listing(&memory)).unwrap(), memory);
+		}
+	}
+}
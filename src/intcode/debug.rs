@@ -0,0 +1,443 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use super::disasm::decode;
+use super::snapshot::Snapshot;
+use super::{IntCodeVM, Memory, NumType, Opcode, State, MAX_MEMORY};
+
+const HALTED: &str = "The program has halted";
+
+/// How many cells `x` shows on a line
+const DUMP_WIDTH: usize = 8;
+
+pub const HELP: &str = "\
+s, step [n]             run n instructions (1 by default), showing each
+c, continue             run until a breakpoint, a watchpoint, input is needed or it halts
+b, break <addr|op>      stop before the instruction at an address, or before any instruction like `out`
+w, watch <addr>         stop after the value at an address changes
+d, delete <addr|op>     remove a breakpoint or watchpoint
+i, info                 list breakpoints and watchpoints
+r, regs                 the instruction pointer, relative base and state
+l, list [addr] [n]      disassemble n instructions from an address, the ip by default
+x <addr> [n]            dump n memory cells (8 by default)
+set <addr> <value>      change a memory cell
+in <values...>          queue numbers as input
+send <text>             queue a line of text as input, newline included
+o, output               show and clear the output waiting to be read
+save <file>             save where the program's got to, queues included
+load <file>             go back to a saved program, breakpoints and watchpoints stay
+q, quit                 stop debugging";
+
+/// Drives an `IntCodeVM` one command at a time. Each command gives back what to show,
+/// the REPL on top (`debug`) only deals with reading lines and printing
+pub struct Debugger {
+	vm: IntCodeVM,
+	breakpoints: BTreeSet<usize>,
+	op_breakpoints: Vec<Opcode>,
+	/// Address watched and the value it had when last looked at
+	watchpoints: BTreeMap<usize, NumType>,
+}
+
+/// What a command did, `Quit` when the debugging session's over
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+	Show(String),
+	Quit,
+}
+
+impl Debugger {
+	pub fn new(memory: Memory) -> Debugger {
+		Debugger::with_vm(IntCodeVM::new(memory))
+	}
+
+	/// Carry on from a snapshot, one that `save` wrote for instance
+	pub fn resume(snapshot: &Snapshot) -> Debugger {
+		Debugger::with_vm(IntCodeVM::from_snapshot(snapshot))
+	}
+
+	fn with_vm(vm: IntCodeVM) -> Debugger {
+		Debugger {
+			vm,
+			breakpoints: BTreeSet::new(),
+			op_breakpoints: Vec::new(),
+			watchpoints: BTreeMap::new(),
+		}
+	}
+
+	pub fn vm(&self) -> &IntCodeVM {
+		&self.vm
+	}
+
+	pub fn command(&mut self, line: &str) -> Result<Reply> {
+		let words: Vec<&str> = line.split_whitespace().collect();
+		let (name, args) = match words.split_first() {
+			Some((name, args)) => (*name, args),
+			None => return Ok(Reply::Show(String::new())),
+		};
+		let text = match name {
+			"s" | "step" => self.step(optional(args.first(), 1)?)?,
+			"c" | "continue" => self.continue_()?,
+			"b" | "break" => self.add_breakpoint(one(args)?)?,
+			"w" | "watch" => {
+				let address = in_memory(one(args)?)?;
+				let value = self.peek(address);
+				self.watchpoints.insert(address, value);
+				format!("Watching {} (now {})", address, value)
+			}
+			"d" | "delete" => self.delete(one(args)?)?,
+			"i" | "info" => self.info(),
+			"r" | "regs" => self.regs(),
+			"l" | "list" => {
+				let address = optional(args.first(), self.vm.register)?;
+				self.list(address, optional(args.get(1), 10)?)
+			}
+			"x" => self.dump(in_memory(args.first().ok_or_else(|| eyre!("x needs an address"))?)?, optional(args.get(1), DUMP_WIDTH)?),
+			"set" => match args {
+				[address, value] => {
+					let (address, value) = (in_memory(address)?, value_of(value)?);
+					self.poke(address, value);
+					format!("{} = {}", address, value)
+				}
+				_ => return Err(eyre!("set needs an address and a value")),
+			},
+			"in" => {
+				let values = args.iter().map(|v| value_of(v)).collect::<Result<Vec<_>>>()?;
+				values.iter().for_each(|&v| self.vm.push_input(v));
+				format!("Queued {} value(s)", values.len())
+			}
+			"send" => {
+				let text = line.trim_start()[name.len()..].trim_start();
+				text.chars().chain(Some('\n')).for_each(|c| self.vm.push_input(c as NumType));
+				format!("Queued {:?}", text)
+			}
+			"o" | "output" => self.take_output().unwrap_or_else(|| "No output".to_string()),
+			"save" => {
+				let path = one(args)?;
+				self.vm.snapshot_with_io().save(Path::new(path)).wrap_err_with(|| format!("Couldn't save to {}", path))?;
+				format!("Saved to {}", path)
+			}
+			"load" => {
+				let path = one(args)?;
+				let snapshot = Snapshot::load(Path::new(path)).wrap_err_with(|| format!("Couldn't load {}", path))?;
+				self.vm.restore(&snapshot);
+				// Only changes from here on are interesting
+				for (&address, value) in self.watchpoints.iter_mut() {
+					*value = self.vm.memory.get(address).copied().unwrap_or(0);
+				}
+				format!("Loaded {}\n{}", path, self.regs())
+			}
+			"h" | "help" => HELP.to_string(),
+			"q" | "quit" => return Ok(Reply::Quit),
+			_ => return Err(eyre!("Unknown command {:?}, try help", name)),
+		};
+		Ok(Reply::Show(text))
+	}
+
+	fn add_breakpoint(&mut self, at: &str) -> Result<String> {
+		if let Some(opcode) = Opcode::from_mnemonic(at) {
+			if !self.op_breakpoints.contains(&opcode) {
+				self.op_breakpoints.push(opcode);
+			}
+			return Ok(format!("Breaking on every {}", at));
+		}
+		let address = number(at)?;
+		self.breakpoints.insert(address);
+		Ok(format!("Breaking at {}", address))
+	}
+
+	fn delete(&mut self, at: &str) -> Result<String> {
+		if let Some(opcode) = Opcode::from_mnemonic(at) {
+			self.op_breakpoints.retain(|&op| op != opcode);
+			return Ok(format!("No longer breaking on {}", at));
+		}
+		let address = number(at)?;
+		let removed = self.breakpoints.remove(&address) | self.watchpoints.remove(&address).is_some();
+		if removed {
+			Ok(format!("Removed {}", address))
+		} else {
+			Err(eyre!("Nothing was set at {}", address))
+		}
+	}
+
+	/// Run one instruction and say anything worth stopping for: it halted, needs input, or a watched cell changed
+	fn run_one(&mut self) -> Result<Option<String>> {
+		match self.vm.state {
+			State::Stopped => return Ok(Some(HALTED.to_string())),
+			State::Reading if !self.vm.has_input() => return Ok(Some(self.waiting())),
+			_ => {}
+		}
+		self.vm.run_one_command()?;
+		let mut changes = Vec::new();
+		for (&address, old) in self.watchpoints.iter_mut() {
+			let new = self.vm.memory.get(address).copied().unwrap_or(0);
+			if new != *old {
+				changes.push(format!("Watchpoint {}: {} -> {}", address, old, new));
+				*old = new;
+			}
+		}
+		if !changes.is_empty() {
+			return Ok(Some(changes.join("\n")));
+		}
+		Ok(match self.vm.state {
+			State::Stopped => Some("Halted".to_string()),
+			State::Reading if !self.vm.has_input() => Some(self.waiting()),
+			_ => None,
+		})
+	}
+
+	fn step(&mut self, steps: usize) -> Result<String> {
+		if self.vm.is_stopped() {
+			return Ok(HALTED.to_string());
+		}
+		let mut lines = Vec::new();
+		for _ in 0..steps {
+			lines.push(self.current());
+			if let Some(reason) = self.run_one()? {
+				lines.push(reason);
+				break;
+			}
+		}
+		lines.extend(self.take_output());
+		Ok(lines.join("\n"))
+	}
+
+	fn continue_(&mut self) -> Result<String> {
+		// Always get off the breakpoint it's sitting on
+		let mut reason = self.run_one()?;
+		while reason.is_none() {
+			if self.at_breakpoint() {
+				reason = Some(format!("Breakpoint\n{}", self.current()));
+			} else {
+				reason = self.run_one()?;
+			}
+		}
+		let mut lines: Vec<String> = self.take_output().into_iter().collect();
+		lines.extend(reason);
+		Ok(lines.join("\n"))
+	}
+
+	fn at_breakpoint(&self) -> bool {
+		if self.breakpoints.contains(&self.vm.register) {
+			return true;
+		}
+		let opcode = self.vm.memory.get(self.vm.register).and_then(|&raw| Opcode::convert_int(raw % 100));
+		opcode.is_some_and(|opcode| self.op_breakpoints.contains(&opcode))
+	}
+
+	fn waiting(&self) -> String {
+		format!("Waiting for input at {}", self.vm.register)
+	}
+
+	fn info(&self) -> String {
+		let mut lines = Vec::new();
+		for address in &self.breakpoints {
+			lines.push(format!("break {}", address));
+		}
+		for opcode in &self.op_breakpoints {
+			lines.push(format!("break {}", opcode.mnemonic()));
+		}
+		for (address, value) in &self.watchpoints {
+			lines.push(format!("watch {} (now {})", address, value));
+		}
+		if lines.is_empty() {
+			return "No breakpoints or watchpoints".to_string();
+		}
+		lines.join("\n")
+	}
+
+	fn regs(&self) -> String {
+		format!(
+			"ip {}, rb {}, {:?}, {} input queued\n{}",
+			self.vm.register,
+			self.vm.relative,
+			self.vm.state,
+			self.vm.input.len(),
+			self.current()
+		)
+	}
+
+	/// The instruction at the ip, as the disassembler would show it
+	fn current(&self) -> String {
+		self.list(self.vm.register, 1)
+	}
+
+	fn list(&self, mut address: usize, count: usize) -> String {
+		let mut lines = Vec::new();
+		for _ in 0..count {
+			if address >= self.vm.memory.len() {
+				break;
+			}
+			match decode(&self.vm.memory, address) {
+				Some(instruction) => {
+					lines.push(format!("{:>5}: {}", address, instruction));
+					address += instruction.size();
+				}
+				None => {
+					lines.push(format!("{:>5}: db {}", address, self.vm.memory[address]));
+					address += 1;
+				}
+			}
+		}
+		lines.join("\n")
+	}
+
+	/// Only goes as far as the memory the program has, past that there's just the one zero to show
+	fn dump(&self, start: usize, count: usize) -> String {
+		let end = start.saturating_add(count).min(self.vm.memory.len().max(start + 1));
+		let addresses: Vec<usize> = (start..end).collect();
+		addresses
+			.chunks(DUMP_WIDTH)
+			.map(|row| {
+				let values = row.iter().map(|&a| self.peek(a).to_string()).collect::<Vec<_>>();
+				format!("{:>5}: {}", row[0], values.join(" "))
+			})
+			.collect::<Vec<_>>()
+			.join("\n")
+	}
+
+	fn peek(&self, address: usize) -> NumType {
+		self.vm.memory.get(address).copied().unwrap_or(0)
+	}
+
+	fn poke(&mut self, address: usize, value: NumType) {
+		self.vm.poke(address, value);
+		if let Some(watched) = self.watchpoints.get_mut(&address) {
+			*watched = value;
+		}
+	}
+
+	/// Everything output since the last look, as text when it all looks like text
+	fn take_output(&mut self) -> Option<String> {
+		let output = self.vm.output().take();
+		if output.is_empty() {
+			return None;
+		}
+		if output.iter().all(|&v| v == 10 || (32..127).contains(&v)) {
+			let text: String = output.iter().map(|&v| v as u8 as char).collect();
+			return Some(text.trim_end_matches('\n').to_string());
+		}
+		Some(format!("Output: {}", output.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ")))
+	}
+}
+
+fn one<'a>(args: &[&'a str]) -> Result<&'a str> {
+	match args {
+		[arg] => Ok(arg),
+		_ => Err(eyre!("Expected one argument")),
+	}
+}
+
+fn number(arg: &str) -> Result<usize> {
+	arg.parse().map_err(|_| eyre!("{:?} isn't an address", arg))
+}
+
+/// An address the program could actually use
+fn in_memory(arg: &str) -> Result<usize> {
+	let address = number(arg)?;
+	if address >= MAX_MEMORY {
+		return Err(eyre!("{} is past the end of memory, addresses go up to {}", address, MAX_MEMORY - 1));
+	}
+	Ok(address)
+}
+
+fn value_of(arg: &str) -> Result<NumType> {
+	arg.parse().map_err(|_| eyre!("{:?} isn't a number", arg))
+}
+
+fn optional(arg: Option<&&str>, default: usize) -> Result<usize> {
+	arg.map_or(Ok(default), |arg| number(arg))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::intcode::asm::assemble;
+
+	fn show(debugger: &mut Debugger, line: &str) -> String {
+		match debugger.command(line).unwrap() {
+			Reply::Show(text) => text,
+			Reply::Quit => panic!("{} quit", line),
+		}
+	}
+
+	fn program() -> Debugger {
+		Debugger::new(
+			assemble(
+				"
+				in [count]
+			loop:
+				out [count]
+				add [count], #-1, [count]
+				jt [count], #loop
+				hlt
+			count:
+				db 0
+				",
+			)
+			.unwrap(),
+		)
+	}
+
+	#[test]
+	fn test_step_and_input() {
+		let mut debugger = program();
+		assert_eq!(show(&mut debugger, "step"), "    0: in [12]\nWaiting for input at 0");
+		show(&mut debugger, "in 2");
+		assert_eq!(show(&mut debugger, "s 2"), "    0: in [12]\n    2: out [12]\nOutput: 2");
+		assert_eq!(show(&mut debugger, "x 12 1"), "   12: 2");
+		assert!(show(&mut debugger, "regs").starts_with("ip 4, rb 0, Ready, 0 input queued"));
+		assert_eq!(show(&mut debugger, "c"), "Output: 1\nHalted");
+		assert_eq!(show(&mut debugger, "s"), "The program has halted");
+	}
+
+	#[test]
+	fn test_breakpoints() {
+		let mut debugger = program();
+		show(&mut debugger, "in 3");
+		show(&mut debugger, "break out");
+		assert_eq!(show(&mut debugger, "c"), "Breakpoint\n    2: out [12]");
+		assert_eq!(show(&mut debugger, "c"), "Output: 3\nBreakpoint\n    2: out [12]");
+		show(&mut debugger, "delete out");
+		show(&mut debugger, "b 11");
+		assert_eq!(show(&mut debugger, "c"), "Output: 2, 1\nBreakpoint\n   11: hlt");
+		assert_eq!(show(&mut debugger, "info"), "break 11");
+	}
+
+	#[test]
+	fn test_watchpoints() {
+		let mut debugger = program();
+		show(&mut debugger, "send A");
+		show(&mut debugger, "watch 12");
+		assert_eq!(show(&mut debugger, "c"), "Watchpoint 12: 0 -> 65");
+		assert_eq!(show(&mut debugger, "c"), "A\nWatchpoint 12: 65 -> 64");
+		assert!(debugger.command("x").is_err());
+		assert_eq!(show(&mut debugger, "x 11 100"), "   11: 99 64");
+		assert_eq!(show(&mut debugger, "x 50"), "   50: 0");
+		assert!(debugger.command(&format!("x {}", MAX_MEMORY)).is_err());
+		assert!(debugger.command(&format!("set {} 1", MAX_MEMORY)).is_err());
+		assert!(debugger.command(&format!("watch {}", MAX_MEMORY)).is_err());
+		assert_eq!(show(&mut debugger, &format!("set {} 1", MAX_MEMORY - 1)), format!("{} = 1", MAX_MEMORY - 1));
+		assert!(debugger.command("frobnicate").is_err());
+		assert_eq!(debugger.command("quit").unwrap(), Reply::Quit);
+	}
+
+	#[test]
+	fn test_save_and_load() {
+		let path = std::env::temp_dir().join(format!("debugger-{}.icvm", std::process::id()));
+		let path = path.to_str().unwrap();
+		let mut debugger = program();
+		show(&mut debugger, "in 2");
+		show(&mut debugger, "s 2");
+		show(&mut debugger, &format!("save {}", path));
+		assert_eq!(show(&mut debugger, "c"), "Output: 1\nHalted");
+		assert!(show(&mut debugger, &format!("load {}", path)).ends_with("ip 4, rb 0, Ready, 0 input queued\n    4: add [12], #-1, [12]"));
+		assert_eq!(show(&mut debugger, "c"), "Output: 1\nHalted");
+		let mut resumed = Debugger::resume(&Snapshot::load(Path::new(path)).unwrap());
+		std::fs::remove_file(path).unwrap();
+		assert_eq!(show(&mut resumed, "c"), "Output: 1\nHalted");
+		assert!(debugger.command("load /nonexistent/file").is_err());
+	}
+}
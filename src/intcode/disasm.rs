@@ -0,0 +1,238 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use super::{Mode, NumType, Opcode};
+
+/// How many values go on each `db` line
+const DATA_WIDTH: usize = 8;
+
+/// One parameter as it's written in memory, the mode comes out of the instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+	pub mode: Mode,
+	pub value: NumType,
+}
+
+impl fmt::Display for Param {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self.mode {
+			Mode::Position => write!(f, "[{}]", self.value),
+			Mode::Immediate => write!(f, "#{}", self.value),
+			Mode::Relative if self.value < 0 => write!(f, "rb{}", self.value),
+			Mode::Relative => write!(f, "rb+{}", self.value),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+	pub opcode: Opcode,
+	pub params: Vec<Param>,
+}
+
+impl Instruction {
+	/// How many values it takes up, opcode included
+	pub fn size(&self) -> usize {
+		self.params.len() + 1
+	}
+
+	/// Back to what goes in memory
+	pub fn encode(&self) -> Vec<NumType> {
+		let modes = self.params.iter().rev().fold(0, |modes, param| modes * 10 + param.mode as NumType);
+		let mut values = vec![modes * 100 + self.opcode as NumType];
+		values.extend(self.params.iter().map(|param| param.value));
+		values
+	}
+}
+
+impl fmt::Display for Instruction {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.opcode.mnemonic())?;
+		for (i, param) in self.params.iter().enumerate() {
+			write!(f, "{}{}", if i == 0 { " " } else { ", " }, param)?;
+		}
+		Ok(())
+	}
+}
+
+/// The instruction at an address, if there's a whole valid one there. Anything that wouldn't encode back
+/// to the same values (leftover mode digits, writing to an immediate) doesn't count
+pub fn decode(memory: &[NumType], address: usize) -> Option<Instruction> {
+	let raw = *memory.get(address)?;
+	if raw < 0 {
+		return None;
+	}
+	let opcode = Opcode::convert_int(raw % 100)?;
+	let mut modes = raw / 100;
+	let mut params = Vec::with_capacity(opcode.params());
+	for i in 0..opcode.params() {
+		let mode = Mode::convert_int(modes % 10)?;
+		modes /= 10;
+		if mode == Mode::Immediate && opcode.writes() == Some(i) {
+			return None;
+		}
+		params.push(Param { mode, value: *memory.get(address + 1 + i)? });
+	}
+	if modes != 0 {
+		return None;
+	}
+	Some(Instruction { opcode, params })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Line {
+	Code { address: usize, instruction: Instruction },
+	/// Values the program never runs
+	Data { address: usize, values: Vec<NumType> },
+}
+
+impl Line {
+	pub fn address(&self) -> usize {
+		match self {
+			Line::Code { address, .. } | Line::Data { address, .. } => *address,
+		}
+	}
+}
+
+impl fmt::Display for Line {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Line::Code { instruction, .. } => write!(f, "{}", instruction),
+			Line::Data { values, .. } => {
+				let list = values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", ");
+				write!(f, "db {}", list)?;
+				// Prompts and messages are easier to spot as text
+				if values.iter().all(|&v| v == 10 || (32..127).contains(&v)) {
+					let text: String = values.iter().map(|&v| v as u8 as char).collect();
+					write!(f, "  ; {:?}", text)?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+/// Follow the program from address 0 to find which addresses start an instruction.
+/// Jumps only go somewhere known when the target is immediate, so the place a call returns to is
+/// found from the return address it pushes: an immediate that points just after a jump that's always taken
+fn reachable(memory: &[NumType]) -> BTreeMap<usize, Instruction> {
+	let mut code = BTreeMap::new();
+	let mut covered = vec![false; memory.len()];
+	let mut after_jumps = BTreeSet::new();
+	let mut todo = vec![0];
+	loop {
+		while let Some(address) = todo.pop() {
+			if address >= memory.len() || covered[address] {
+				continue;
+			}
+			let instruction = match decode(memory, address) {
+				Some(instruction) => instruction,
+				None => continue,
+			};
+			let next = address + instruction.size();
+			if covered[address..next].iter().any(|&c| c) {
+				continue;
+			}
+			covered[address..next].iter_mut().for_each(|c| *c = true);
+			match instruction.opcode {
+				Opcode::Stop => {}
+				Opcode::JumpIfTrue | Opcode::JumpIfFalse => {
+					let (condition, target) = (instruction.params[0], instruction.params[1]);
+					if target.mode == Mode::Immediate && target.value >= 0 {
+						todo.push(target.value as usize);
+					}
+					let always = condition.mode == Mode::Immediate
+						&& (condition.value != 0) == (instruction.opcode == Opcode::JumpIfTrue);
+					if always {
+						after_jumps.insert(next);
+					} else {
+						todo.push(next);
+					}
+				}
+				_ => todo.push(next),
+			}
+			code.insert(address, instruction);
+		}
+		let returns: BTreeSet<usize> = code
+			.values()
+			.flat_map(|instruction| instruction.params.iter())
+			.filter(|param| param.mode == Mode::Immediate && param.value >= 0)
+			.map(|param| param.value as usize)
+			.filter(|address| after_jumps.contains(address))
+			.collect();
+		if returns.is_empty() {
+			return code;
+		}
+		for address in returns {
+			after_jumps.remove(&address);
+			todo.push(address);
+		}
+	}
+}
+
+/// Split memory up into the instructions that can be reached and the data around them
+pub fn disassemble(memory: &[NumType]) -> Vec<Line> {
+	let mut code = reachable(memory);
+	let mut lines = Vec::new();
+	let mut address = 0;
+	while address < memory.len() {
+		if let Some(instruction) = code.remove(&address) {
+			let next = address + instruction.size();
+			lines.push(Line::Code { address, instruction });
+			address = next;
+		} else {
+			let end = code.keys().next().copied().unwrap_or(memory.len());
+			for chunk in memory[address..end].chunks(DATA_WIDTH) {
+				lines.push(Line::Data { address, values: chunk.to_vec() });
+				address += chunk.len();
+			}
+		}
+	}
+	lines
+}
+
+/// A line per instruction or run of data, each starting with its address
+pub fn listing(memory: &[NumType]) -> String {
+	let width = memory.len().saturating_sub(1).to_string().len();
+	disassemble(memory)
+		.iter()
+		.map(|line| format!("{:>width$}: {}", line.address(), line, width = width))
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_decode() {
+		let memory = vec![1002, 4, 3, 4, 33, 21101, 1, -2, 3, 11101, 0, 0, 0, 10099, 1001, 1];
+		let instruction = decode(&memory, 0).unwrap();
+		assert_eq!(instruction.to_string(), "mul [4], #3, [4]");
+		assert_eq!(instruction.encode(), &memory[0..4]);
+		assert_eq!(decode(&memory, 5).unwrap().to_string(), "add #1, #-2, rb+3");
+		assert_eq!(decode(&memory, 4), None);
+		// Writes to an immediate, then a mode with no parameter to go with it
+		assert_eq!(decode(&memory, 9), None);
+		assert_eq!(decode(&memory, 13), None);
+		// Runs off the end
+		assert_eq!(decode(&memory, 14), None);
+	}
+
+	#[test]
+	fn test_listing() {
+		let memory = vec![109, 1, 204, -1, 1105, 1, 9, 72, 105, 99];
+		assert_eq!(listing(&memory), "0: arb #1\n2: out rb-1\n4: jt #1, #9\n7: db 72, 105  ; \"Hi\"\n9: hlt");
+	}
+
+	#[test]
+	fn test_calls_return() {
+		// Push 9 to return to, call 12, then stop. 12 returns through the relative base
+		let memory = vec![109, 20, 21101, 9, 0, 0, 1105, 1, 12, 99, 7, 7, 2106, 0, 0];
+		let lines = disassemble(&memory);
+		assert!(lines.contains(&Line::Code { address: 9, instruction: decode(&memory, 9).unwrap() }));
+		assert!(lines.contains(&Line::Data { address: 10, values: vec![7, 7] }));
+		assert_eq!(lines.last().unwrap().to_string(), "jf #0, rb+0");
+	}
+}
@@ -0,0 +1,204 @@
+use std::collections::VecDeque;
+use std::sync::mpsc::{Receiver, Sender, SyncSender};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use super::NumType;
+
+/// Where a VM's input comes from once it's been given one with `IntCodeVM::set_input`.
+/// Anything from `push_input` still goes first
+pub trait Input: Send {
+	/// The next value if there's one already, without waiting for it
+	fn try_read(&mut self) -> Option<NumType>;
+
+	/// Wait for the next value, `None` if there'll never be one. Sources that can't wait just try
+	fn read(&mut self) -> Option<NumType> {
+		self.try_read()
+	}
+}
+
+/// Where a VM's output goes once it's been given one with `IntCodeVM::set_output`
+pub trait Output: Send {
+	fn write(&mut self, value: NumType);
+}
+
+/// The queue VMs use unless they're told otherwise. Clones share the same values, so one VM's output queue
+/// can be another's input (see `IntCodeVM::network_to`), on the same thread or not
+#[derive(Debug, Clone, Default)]
+pub struct Queue(Arc<Mutex<VecDeque<NumType>>>);
+
+impl Queue {
+	pub fn new() -> Queue {
+		Queue::default()
+	}
+
+	/// Get at the values directly. A panic elsewhere while it was held doesn't stop it being used
+	pub fn lock(&self) -> MutexGuard<'_, VecDeque<NumType>> {
+		self.0.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+	}
+
+	pub fn push(&self, value: NumType) {
+		self.lock().push_back(value);
+	}
+
+	pub fn pop(&self) -> Option<NumType> {
+		self.lock().pop_front()
+	}
+
+	/// Everything in it, leaving it empty
+	pub fn take(&self) -> VecDeque<NumType> {
+		std::mem::take(&mut *self.lock())
+	}
+
+	pub fn len(&self) -> usize {
+		self.lock().len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.lock().is_empty()
+	}
+}
+
+impl From<Vec<NumType>> for Queue {
+	fn from(values: Vec<NumType>) -> Queue {
+		Queue(Arc::new(Mutex::new(values.into())))
+	}
+}
+
+impl Input for Queue {
+	fn try_read(&mut self) -> Option<NumType> {
+		self.pop()
+	}
+}
+
+impl Output for Queue {
+	fn write(&mut self, value: NumType) {
+		self.push(value);
+	}
+}
+
+impl Input for Receiver<NumType> {
+	fn try_read(&mut self) -> Option<NumType> {
+		self.try_recv().ok()
+	}
+
+	fn read(&mut self) -> Option<NumType> {
+		self.recv().ok()
+	}
+}
+
+// A closed channel means nothing's listening any more, like an amplifier that's already halted,
+// so what would have gone to it is dropped
+impl Output for Sender<NumType> {
+	fn write(&mut self, value: NumType) {
+		let _ = self.send(value);
+	}
+}
+
+impl Output for SyncSender<NumType> {
+	fn write(&mut self, value: NumType) {
+		let _ = self.send(value);
+	}
+}
+
+impl Input for crossbeam_channel::Receiver<NumType> {
+	fn try_read(&mut self) -> Option<NumType> {
+		self.try_recv().ok()
+	}
+
+	fn read(&mut self) -> Option<NumType> {
+		self.recv().ok()
+	}
+}
+
+impl Output for crossbeam_channel::Sender<NumType> {
+	fn write(&mut self, value: NumType) {
+		let _ = self.send(value);
+	}
+}
+
+/// Input from a function, called whenever the program reads. `None` makes it wait, and it's asked again when the
+/// VM next runs
+pub struct InputFn<F>(pub F);
+
+impl<F: FnMut() -> Option<NumType> + Send> Input for InputFn<F> {
+	fn try_read(&mut self) -> Option<NumType> {
+		(self.0)()
+	}
+}
+
+/// Output to a function, called with each value as it's written
+pub struct OutputFn<F>(pub F);
+
+impl<F: FnMut(NumType) + Send> Output for OutputFn<F> {
+	fn write(&mut self, value: NumType) {
+		(self.0)(value)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::intcode::{IntCodeVM, State};
+	use std::sync::mpsc;
+	use std::thread;
+
+	#[test]
+	fn test_amplifiers_on_threads() {
+		let memory = vec![
+			3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1, 28, 1005, 28, 6, 99,
+			0, 0, 5,
+		];
+		let (senders, receivers): (Vec<_>, Vec<_>) = (0..5).map(|_| mpsc::channel()).unzip();
+		// The last amplifier's output goes back round to the first, and is kept to see what it was at the end
+		let last = Queue::new();
+		let mut handles = Vec::new();
+		for (i, receiver) in receivers.into_iter().enumerate() {
+			let mut vm = IntCodeVM::new(memory.clone());
+			vm.push_input(9 - i as NumType);
+			if i == 0 {
+				vm.push_input(0);
+			}
+			vm.set_input(receiver);
+			let mut next = senders[(i + 1) % 5].clone();
+			if i == 4 {
+				let last = last.clone();
+				vm.set_output(OutputFn(move |value| {
+					last.push(value);
+					next.write(value);
+				}));
+			} else {
+				vm.set_output(next);
+			}
+			handles.push(thread::spawn(move || vm.run_blocking()));
+		}
+		for handle in handles {
+			assert_eq!(handle.join().unwrap(), Ok(State::Stopped));
+		}
+		assert_eq!(last.take().back(), Some(&139629729));
+	}
+
+	#[test]
+	fn test_channels_and_callbacks() {
+		// Add up whatever's read until a 0
+		let memory = vec![3, 12, 1, 12, 13, 13, 1005, 12, 0, 4, 13, 99, 0, 0];
+		let (sender, receiver) = crossbeam_channel::unbounded();
+		let (results, got) = crossbeam_channel::unbounded();
+		let mut vm = IntCodeVM::new(memory.clone());
+		vm.set_input(receiver);
+		vm.set_output(results);
+		for value in &[1, 2, 3] {
+			sender.send(*value).unwrap();
+		}
+		assert_eq!(vm.run_all(), Ok(State::Reading));
+		drop(sender);
+		// Nothing else can arrive, so it gives up rather than waiting forever
+		assert_eq!(vm.run_blocking(), Ok(State::Reading));
+		assert!(got.try_recv().is_err());
+
+		let mut values = vec![0, 5, 4].into_iter();
+		let mut vm = IntCodeVM::new(memory);
+		vm.set_input(InputFn(move || values.next_back()));
+		assert_eq!(vm.run_all(), Ok(State::Stopped));
+		assert_eq!(vm.pop_output(), Some(9));
+	}
+}
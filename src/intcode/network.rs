@@ -0,0 +1,456 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use super::{IntCodeVM, Memory, NumType, VmError};
+
+/// Something one node sent another (or a handler). Programs output the address then the values
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+	pub from: NumType,
+	pub to: NumType,
+	pub values: Vec<NumType>,
+}
+
+/// What a handler wants to happen next
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+	Continue,
+	Send(Packet),
+	/// Stop the whole network, with an answer
+	Stop(NumType),
+}
+
+/// Something listening on an address no node has, like day 23's NAT on 255
+pub trait Handler {
+	fn receive(&mut self, packet: &Packet) -> Action;
+
+	/// Every node's idle. If no handler does anything about it, nothing ever will, and the network stops
+	fn idle(&mut self) -> Action {
+		Action::Continue
+	}
+}
+
+/// Stops the network with the last value of the first packet it gets
+#[derive(Debug, Clone, Default)]
+pub struct FirstPacket;
+
+impl Handler for FirstPacket {
+	fn receive(&mut self, packet: &Packet) -> Action {
+		Action::Stop(*packet.values.last().unwrap_or(&0))
+	}
+}
+
+/// Keeps the last packet it was sent and passes it on to `to` whenever the network goes idle. Passing on the
+/// same last value twice in a row stops the network with that value
+#[derive(Debug, Clone)]
+pub struct Nat {
+	address: NumType,
+	to: NumType,
+	last: Option<Vec<NumType>>,
+	sent: Option<NumType>,
+}
+
+impl Nat {
+	pub fn new(address: NumType, to: NumType) -> Nat {
+		Nat { address, to, last: None, sent: None }
+	}
+}
+
+impl Handler for Nat {
+	fn receive(&mut self, packet: &Packet) -> Action {
+		self.last = Some(packet.values.clone());
+		Action::Continue
+	}
+
+	fn idle(&mut self) -> Action {
+		let values = match &self.last {
+			Some(values) => values.clone(),
+			None => return Action::Continue,
+		};
+		let last = values.last().copied();
+		if let (Some(last), Some(sent)) = (last, self.sent) {
+			if last == sent {
+				return Action::Stop(last);
+			}
+		}
+		self.sent = last;
+		Action::Send(Packet { from: self.address, to: self.to, values })
+	}
+}
+
+/// The order nodes get to run in. Each one is the same every time for the same network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+	/// One instruction from each node in turn
+	RoundRobin,
+	/// Each node in turn runs until it's waiting for input it hasn't got
+	UntilBlocked,
+	/// The nodes in a shuffled order each round, running a few instructions each, from the seed
+	Random(u64),
+}
+
+/// How far a network got
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+	/// A handler stopped it with this
+	Stopped(NumType),
+	/// Everything went idle and no handler had anything to send
+	Idle,
+	/// Every node halted
+	Halted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkError {
+	pub node: usize,
+	pub error: VmError,
+}
+
+impl fmt::Display for NetworkError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "node {}: {}", self.node, self.error)
+	}
+}
+
+impl Error for NetworkError {}
+
+/// A packet that went somewhere, and when. `dropped` is for addresses nothing was on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+	pub round: u64,
+	pub packet: Packet,
+	pub dropped: bool,
+}
+
+struct Node {
+	vm: IntCodeVM,
+	address: NumType,
+	/// How many times it's been given `empty` since it last had something real to read or sent anything
+	polls: usize,
+}
+
+/// Intcode programs that send each other packets, run on one thread in a fixed order so a run can be repeated.
+/// A packet is an address followed by `arity` values, delivered to the node or handler at that address
+pub struct Network {
+	nodes: Vec<Node>,
+	routes: BTreeMap<NumType, usize>,
+	handlers: BTreeMap<NumType, Box<dyn Handler>>,
+	arity: usize,
+	empty: Option<NumType>,
+	schedule: Schedule,
+	logging: bool,
+	log: Vec<LogEntry>,
+	round: u64,
+}
+
+impl Network {
+	/// `routes` says which program is at each address. A program with no address can still send
+	pub fn new(programs: Vec<Memory>, routes: BTreeMap<NumType, usize>) -> Network {
+		let mut addresses = BTreeMap::new();
+		for (&address, &node) in routes.iter().rev() {
+			addresses.insert(node, address);
+		}
+		let nodes = programs
+			.into_iter()
+			.enumerate()
+			.map(|(i, memory)| Node { vm: IntCodeVM::new(memory), address: *addresses.get(&i).unwrap_or(&-1), polls: 0 })
+			.collect();
+		Network {
+			nodes,
+			routes,
+			handlers: BTreeMap::new(),
+			arity: 2,
+			empty: None,
+			schedule: Schedule::RoundRobin,
+			logging: false,
+			log: Vec::new(),
+			round: 0,
+		}
+	}
+
+	/// `count` copies of a program at addresses 0 and up, each given its address as its first input
+	pub fn numbered(program: Memory, count: usize) -> Network {
+		let routes = (0..count).map(|i| (i as NumType, i)).collect();
+		let mut network = Network::new(vec![program; count], routes);
+		for node in network.nodes.iter_mut() {
+			node.vm.push_input(node.address);
+		}
+		network
+	}
+
+	/// How many values follow the address in a packet, 2 (x and y) to start with
+	pub fn set_arity(&mut self, arity: usize) {
+		self.arity = arity;
+	}
+
+	/// What a node reads when it's waiting and there's nothing for it. Without one it just waits
+	pub fn set_empty_input(&mut self, empty: Option<NumType>) {
+		self.empty = empty;
+	}
+
+	pub fn set_schedule(&mut self, schedule: Schedule) {
+		self.schedule = schedule;
+	}
+
+	pub fn add_handler<H: Handler + 'static>(&mut self, address: NumType, handler: H) {
+		self.handlers.insert(address, Box::new(handler));
+	}
+
+	/// Keep every packet sent from now on
+	pub fn set_logging(&mut self, logging: bool) {
+		self.logging = logging;
+	}
+
+	pub fn node(&self, node: usize) -> &IntCodeVM {
+		&self.nodes[node].vm
+	}
+
+	pub fn node_mut(&mut self, node: usize) -> &mut IntCodeVM {
+		&mut self.nodes[node].vm
+	}
+
+	pub fn log(&self) -> &[LogEntry] {
+		&self.log
+	}
+
+	/// The log as CSV, values space separated
+	pub fn write_log<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		writeln!(writer, "round,from,to,values,dropped")?;
+		for entry in &self.log {
+			let values: Vec<String> = entry.packet.values.iter().map(|v| v.to_string()).collect();
+			writeln!(
+				writer,
+				"{},{},{},{},{}",
+				entry.round,
+				entry.packet.from,
+				entry.packet.to,
+				values.join(" "),
+				entry.dropped
+			)?;
+		}
+		Ok(())
+	}
+
+	/// Run until a handler stops it, everything's idle with nothing left to do, or every node has halted
+	pub fn run(&mut self) -> Result<Outcome, NetworkError> {
+		let mut rng = match self.schedule {
+			Schedule::Random(seed) => Some(StdRng::seed_from_u64(seed)),
+			_ => None,
+		};
+		loop {
+			self.round += 1;
+			let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+			if let Some(rng) = rng.as_mut() {
+				order.shuffle(rng);
+			}
+			for node in order {
+				let budget = match (self.schedule, rng.as_mut()) {
+					(Schedule::UntilBlocked, _) => None,
+					(_, Some(rng)) => Some(rng.gen_range(1, 9)),
+					_ => Some(1),
+				};
+				if let Some(answer) = self.turn(node, budget)? {
+					return Ok(Outcome::Stopped(answer));
+				}
+			}
+			if self.nodes.iter().all(|node| node.vm.is_stopped()) {
+				return Ok(Outcome::Halted);
+			}
+			if self.is_idle() {
+				let mut acted = false;
+				let addresses: Vec<NumType> = self.handlers.keys().copied().collect();
+				for address in addresses {
+					let action = self.handlers.get_mut(&address).unwrap().idle();
+					match action {
+						Action::Continue => {}
+						Action::Send(packet) => {
+							acted = true;
+							if let Some(answer) = self.deliver(packet) {
+								return Ok(Outcome::Stopped(answer));
+							}
+						}
+						Action::Stop(answer) => return Ok(Outcome::Stopped(answer)),
+					}
+				}
+				if !acted {
+					return Ok(Outcome::Idle);
+				}
+			}
+		}
+	}
+
+	/// Nothing's waiting to be read or halfway through being sent, and every node that's still going has asked
+	/// for input at least twice since it last sent or got anything (or is stuck waiting, without `empty`)
+	fn is_idle(&self) -> bool {
+		self.nodes.iter().filter(|node| !node.vm.is_stopped()).all(|node| {
+			!node.vm.has_input()
+				&& !node.vm.has_output()
+				&& match self.empty {
+					Some(_) => node.polls >= 2,
+					None => node.vm.is_waiting(),
+				}
+		})
+	}
+
+	/// Run one node for `budget` instructions, or until it's blocked. It gets `empty` at most once a turn
+	fn turn(&mut self, node: usize, budget: Option<usize>) -> Result<Option<NumType>, NetworkError> {
+		let mut polled = false;
+		let mut steps = 0;
+		while steps < budget.unwrap_or(usize::MAX) {
+			let current = &mut self.nodes[node];
+			if current.vm.is_stopped() {
+				break;
+			}
+			// Anything real to read means it's busy again, however it got there
+			if current.vm.has_input() {
+				current.polls = 0;
+			} else if current.vm.is_waiting() {
+				match self.empty {
+					Some(empty) if !polled => {
+						current.vm.push_input(empty);
+						current.polls += 1;
+						polled = true;
+					}
+					_ => break,
+				}
+			}
+			current.vm.run_one_command().map_err(|error| NetworkError { node, error })?;
+			steps += 1;
+			if current.vm.has_output_amt(self.arity + 1) {
+				current.polls = 0;
+				let to = current.vm.pop_output().unwrap();
+				let values = (0..self.arity).map(|_| current.vm.pop_output().unwrap()).collect();
+				let packet = Packet { from: current.address, to, values };
+				if let Some(answer) = self.deliver(packet) {
+					return Ok(Some(answer));
+				}
+			}
+		}
+		Ok(None)
+	}
+
+	/// Hand a packet to whatever's at its address, and follow up on anything a handler does about it
+	fn deliver(&mut self, mut packet: Packet) -> Option<NumType> {
+		loop {
+			let dropped = !self.routes.contains_key(&packet.to) && !self.handlers.contains_key(&packet.to);
+			if self.logging {
+				self.log.push(LogEntry { round: self.round, packet: packet.clone(), dropped });
+			}
+			if let Some(&node) = self.routes.get(&packet.to) {
+				let vm = &mut self.nodes[node].vm;
+				packet.values.iter().for_each(|&value| vm.push_input(value));
+				return None;
+			}
+			let handler = self.handlers.get_mut(&packet.to)?;
+			match handler.receive(&packet) {
+				Action::Continue => return None,
+				Action::Send(next) => packet = next,
+				Action::Stop(answer) => return Some(answer),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::intcode::asm::assemble;
+
+	// Boots with its address, then passes anything it's sent on to the next address up, plus one
+	fn relay() -> Memory {
+		assemble(
+			"
+			        in [addr]
+			        add [addr], #1, [next]
+			loop:   in [value]
+			        eq [value], #-1, [flag]
+			        jt [flag], #loop
+			        out [next]
+			        add [value], #1, [value]
+			        out [value]
+			        jt #1, #loop
+			addr:   db 0
+			next:   db 0
+			value:  db 0
+			flag:   db 0
+			",
+		)
+		.unwrap()
+	}
+
+	fn relays(schedule: Schedule) -> Network {
+		let mut network = Network::numbered(relay(), 3);
+		network.set_arity(1);
+		network.set_empty_input(Some(-1));
+		network.set_schedule(schedule);
+		network.set_logging(true);
+		network
+	}
+
+	#[test]
+	fn test_relay() {
+		for &schedule in &[Schedule::RoundRobin, Schedule::UntilBlocked, Schedule::Random(7)] {
+			let mut network = relays(schedule);
+			network.add_handler(3, FirstPacket);
+			network.node_mut(0).push_input(10);
+			assert_eq!(network.run(), Ok(Outcome::Stopped(13)));
+			let sent: Vec<_> = network.log().iter().map(|entry| (entry.packet.from, entry.packet.to)).collect();
+			assert_eq!(sent, vec![(0, 1), (1, 2), (2, 3)]);
+		}
+	}
+
+	#[test]
+	fn test_idle_and_log() {
+		// Nothing to do, and nothing listening at 3 so what gets there is dropped
+		let mut network = relays(Schedule::RoundRobin);
+		assert_eq!(network.run(), Ok(Outcome::Idle));
+		assert!(network.log().is_empty());
+		network.node_mut(1).push_input(5);
+		assert_eq!(network.run(), Ok(Outcome::Idle));
+		let mut csv = Vec::new();
+		network.write_log(&mut csv).unwrap();
+		let csv = String::from_utf8(csv).unwrap();
+		let lines: Vec<_> = csv.lines().collect();
+		assert_eq!(lines.len(), 3);
+		assert_eq!(lines[0], "round,from,to,values,dropped");
+		assert!(lines[1].ends_with(",1,2,6,false"));
+		assert!(lines[2].ends_with(",2,3,7,true"));
+	}
+
+	#[test]
+	fn test_nat_and_errors() {
+		// The NAT keeps sending 0 the same packet, which it passes straight back to the NAT
+		let bounce = assemble(
+			"
+			        in [addr]
+			loop:   in [value]
+			        eq [value], #-1, [flag]
+			        jt [flag], #loop
+			        out #255
+			        out [value]
+			        jt #1, #loop
+			addr:   db 0
+			value:  db 0
+			flag:   db 0
+			",
+		)
+		.unwrap();
+		let mut network = Network::numbered(bounce, 2);
+		network.set_arity(1);
+		network.set_empty_input(Some(-1));
+		network.add_handler(255, Nat::new(255, 0));
+		network.node_mut(1).push_input(42);
+		assert_eq!(network.run(), Ok(Outcome::Stopped(42)));
+
+		// Each overwrites its next instruction with its address, and 0 isn't an opcode
+		let mut network = Network::numbered(vec![3, 2, 99], 2);
+		let error = NetworkError { node: 0, error: VmError::UnknownOpcode { ip: 2, instruction: 0 } };
+		assert_eq!(network.run(), Err(error));
+	}
+}
@@ -0,0 +1,212 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+
+use super::{Memory, NumType, State, MAX_MEMORY};
+
+const MAGIC: &[u8; 4] = b"ICVM";
+const VERSION: u8 = 1;
+
+/// Everything needed to carry a VM on from where it was, see `IntCodeVM::snapshot`.
+/// What it was plugged into (tracer, input source, output sink) isn't part of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+	pub memory: Memory,
+	pub register: usize,
+	pub relative: NumType,
+	pub state: State,
+	/// What was waiting in its own queues, if that was asked for. Values still in an input source aren't
+	pub io: Option<Queues>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Queues {
+	pub input: Vec<NumType>,
+	pub output: Vec<NumType>,
+}
+
+/// The file format is `ICVM`, a version byte, the state and whether the queues are there, then the ip,
+/// relative base and memory (and the queues, if there) as LEB128 varints. Signed values are zigzagged first,
+/// so the small numbers most memory holds take a byte or two
+impl Snapshot {
+	pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		writer.write_all(MAGIC)?;
+		let state = match self.state {
+			State::Ready => 0,
+			State::Stopped => 1,
+			State::Reading => 2,
+		};
+		writer.write_all(&[VERSION, state, self.io.is_some() as u8])?;
+		write_varint(writer, self.register as u64)?;
+		write_signed(writer, self.relative)?;
+		write_values(writer, &self.memory)?;
+		if let Some(io) = &self.io {
+			write_values(writer, &io.input)?;
+			write_values(writer, &io.output)?;
+		}
+		Ok(())
+	}
+
+	pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Snapshot> {
+		let mut header = [0; 7];
+		reader.read_exact(&mut header)?;
+		if &header[..4] != MAGIC {
+			return Err(invalid("not an intcode snapshot".to_string()));
+		}
+		if header[4] != VERSION {
+			return Err(invalid(format!("snapshot version {} isn't supported", header[4])));
+		}
+		let state = match header[5] {
+			0 => State::Ready,
+			1 => State::Stopped,
+			2 => State::Reading,
+			other => return Err(invalid(format!("{} isn't a state", other))),
+		};
+		let has_io = match header[6] {
+			0 => false,
+			1 => true,
+			other => return Err(invalid(format!("{} isn't a flag", other))),
+		};
+		let register = read_varint(reader)? as usize;
+		let relative = read_signed(reader)?;
+		let memory = read_values(reader)?;
+		let io = if has_io {
+			Some(Queues { input: read_values(reader)?, output: read_values(reader)? })
+		} else {
+			None
+		};
+		Ok(Snapshot { memory, register, relative, state, io })
+	}
+
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		self.write_to(&mut bytes).expect("Writing to a Vec can't fail");
+		bytes
+	}
+
+	pub fn from_bytes(mut bytes: &[u8]) -> io::Result<Snapshot> {
+		let snapshot = Snapshot::read_from(&mut bytes)?;
+		if !bytes.is_empty() {
+			return Err(invalid(format!("{} byte(s) left over after the snapshot", bytes.len())));
+		}
+		Ok(snapshot)
+	}
+
+	pub fn save(&self, path: &Path) -> io::Result<()> {
+		let mut writer = BufWriter::new(File::create(path)?);
+		self.write_to(&mut writer)?;
+		writer.flush()
+	}
+
+	/// Held to the same standard as `from_bytes`, so a file with something after the snapshot isn't taken for one
+	pub fn load(path: &Path) -> io::Result<Snapshot> {
+		Snapshot::from_bytes(&fs::read(path)?)
+	}
+}
+
+fn invalid(message: String) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> io::Result<()> {
+	loop {
+		let byte = (value & 0x7f) as u8;
+		value >>= 7;
+		if value == 0 {
+			return writer.write_all(&[byte]);
+		}
+		writer.write_all(&[byte | 0x80])?;
+	}
+}
+
+fn write_signed<W: Write>(writer: &mut W, value: NumType) -> io::Result<()> {
+	write_varint(writer, ((value << 1) ^ (value >> 63)) as u64)
+}
+
+fn write_values<W: Write>(writer: &mut W, values: &[NumType]) -> io::Result<()> {
+	write_varint(writer, values.len() as u64)?;
+	values.iter().try_for_each(|&value| write_signed(writer, value))
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> io::Result<u64> {
+	let mut value = 0;
+	for shift in (0..64).step_by(7) {
+		let mut byte = [0];
+		reader.read_exact(&mut byte)?;
+		value |= ((byte[0] & 0x7f) as u64) << shift;
+		if byte[0] & 0x80 == 0 {
+			return Ok(value);
+		}
+	}
+	Err(invalid("a number is too long".to_string()))
+}
+
+fn read_signed<R: Read>(reader: &mut R) -> io::Result<NumType> {
+	let value = read_varint(reader)?;
+	Ok((value >> 1) as NumType ^ -((value & 1) as NumType))
+}
+
+fn read_values<R: Read>(reader: &mut R) -> io::Result<Vec<NumType>> {
+	let len = read_varint(reader)? as usize;
+	// The length could be anything in a corrupt file, so don't trust it for an allocation
+	if len > MAX_MEMORY {
+		return Err(invalid(format!("{} values is more than a VM can hold", len)));
+	}
+	(0..len).map(|_| read_signed(reader)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::intcode::IntCodeVM;
+
+	#[test]
+	fn test_bytes_round_trip() {
+		let snapshot = Snapshot {
+			memory: vec![0, 1, -1, 99, NumType::MAX, NumType::MIN, 1 << 40],
+			register: 300,
+			relative: -64,
+			state: State::Reading,
+			io: Some(Queues { input: vec![10, -3], output: vec![] }),
+		};
+		let bytes = snapshot.to_bytes();
+		assert_eq!(&bytes[..10], &[b'I', b'C', b'V', b'M', 1, 2, 1, 0xac, 0x02, 0x7f]);
+		assert_eq!(Snapshot::from_bytes(&bytes).unwrap(), snapshot);
+		let without_io = Snapshot { io: None, ..snapshot };
+		assert_eq!(Snapshot::from_bytes(&without_io.to_bytes()).unwrap(), without_io);
+	}
+
+	#[test]
+	fn test_bad_bytes() {
+		let bytes = IntCodeVM::new(vec![99]).snapshot().to_bytes();
+		let error = |bytes: &[u8]| Snapshot::from_bytes(bytes).unwrap_err().to_string();
+		assert_eq!(error(b"ICVN\x01\x00\x00"), "not an intcode snapshot");
+		assert_eq!(error(b"ICVM\x02\x00\x00"), "snapshot version 2 isn't supported");
+		assert_eq!(error(b"ICVM\x01\x03\x00"), "3 isn't a state");
+		assert!(Snapshot::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+		let mut extra = bytes.clone();
+		extra.push(0);
+		assert_eq!(error(&extra), "1 byte(s) left over after the snapshot");
+		// A length that claims the rest of the address space
+		assert!(error(b"ICVM\x01\x00\x00\x00\x00\xff\xff\xff\xff\x0f").contains("more than a VM can hold"));
+	}
+
+	#[test]
+	fn test_save_and_load() {
+		let path = std::env::temp_dir().join(format!("intcode-snapshot-{}.icvm", std::process::id()));
+		let snapshot = IntCodeVM::new(vec![104, 7, 99]).snapshot_with_io();
+		snapshot.save(&path).unwrap();
+		let loaded = Snapshot::load(&path);
+		// Two snapshots in one file, or one cut short, aren't loaded
+		let mut bytes = snapshot.to_bytes();
+		bytes.extend(snapshot.to_bytes());
+		std::fs::write(&path, &bytes).unwrap();
+		let doubled = Snapshot::load(&path);
+		std::fs::write(&path, &bytes[..bytes.len() / 2 - 1]).unwrap();
+		let truncated = Snapshot::load(&path);
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(loaded.unwrap(), snapshot);
+		assert!(doubled.unwrap_err().to_string().contains("left over"));
+		assert!(truncated.is_err());
+	}
+}
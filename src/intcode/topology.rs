@@ -0,0 +1,139 @@
+use super::network::NetworkError;
+use super::{IntCodeVM, Memory, NumType, State};
+
+/// How a topology ended up once nothing else could happen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+	/// Everything each node output, in order, whether it went anywhere or not
+	pub outputs: Vec<Vec<NumType>>,
+	pub states: Vec<State>,
+}
+
+impl Report {
+	pub fn halted(&self) -> Vec<usize> {
+		self.nodes_in(State::Stopped)
+	}
+
+	/// The nodes still waiting for input that's never coming
+	pub fn blocked(&self) -> Vec<usize> {
+		self.nodes_in(State::Reading)
+	}
+
+	fn nodes_in(&self, state: State) -> Vec<usize> {
+		(0..self.states.len()).filter(|&node| self.states[node] == state).collect()
+	}
+}
+
+/// VMs wired together in any shape: each edge copies everything one node outputs to the end of another's input,
+/// so a node can feed several others, be fed by several, or feed itself back round a loop
+#[derive(Debug, Clone, Default)]
+pub struct Topology {
+	nodes: Vec<IntCodeVM>,
+	edges: Vec<Vec<usize>>,
+}
+
+impl Topology {
+	pub fn new() -> Topology {
+		Topology::default()
+	}
+
+	/// A line of copies of a program, each given its own inputs first (day 7's phase settings), and with
+	/// `looped` the last one feeding back into the first
+	pub fn chain(memory: &Memory, inputs: Vec<Vec<NumType>>, looped: bool) -> Topology {
+		let mut topology = Topology::new();
+		for node_inputs in inputs {
+			let node = topology.add_node(memory.clone(), &node_inputs);
+			if node > 0 {
+				topology.connect(node - 1, node);
+			}
+		}
+		if looped && !topology.nodes.is_empty() {
+			topology.connect(topology.nodes.len() - 1, 0);
+		}
+		topology
+	}
+
+	/// Add a node with some input already queued for it, and get its index
+	pub fn add_node(&mut self, memory: Memory, inputs: &[NumType]) -> usize {
+		let mut vm = IntCodeVM::new(memory);
+		inputs.iter().for_each(|&value| vm.push_input(value));
+		self.nodes.push(vm);
+		self.edges.push(Vec::new());
+		self.nodes.len() - 1
+	}
+
+	pub fn connect(&mut self, from: usize, to: usize) {
+		self.edges[from].push(to);
+	}
+
+	pub fn node(&self, node: usize) -> &IntCodeVM {
+		&self.nodes[node]
+	}
+
+	pub fn node_mut(&mut self, node: usize) -> &mut IntCodeVM {
+		&mut self.nodes[node]
+	}
+
+	/// Run each node in turn until it halts or is waiting for input, passing on what it output, until a whole
+	/// round goes by without anything being output
+	pub fn run(&mut self) -> Result<Report, NetworkError> {
+		let mut outputs = vec![Vec::new(); self.nodes.len()];
+		loop {
+			let mut moved = false;
+			for (node, sent) in outputs.iter_mut().enumerate() {
+				if self.nodes[node].is_stopped() {
+					continue;
+				}
+				self.nodes[node].run_all().map_err(|error| NetworkError { node, error })?;
+				for value in self.nodes[node].output().take() {
+					moved = true;
+					sent.push(value);
+					for &to in &self.edges[node] {
+						self.nodes[to].push_input(value);
+					}
+				}
+			}
+			if !moved {
+				let states = self.nodes.iter().map(|vm| if vm.is_stopped() { State::Stopped } else { State::Reading }).collect();
+				return Ok(Report { outputs, states });
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::intcode::asm::assemble;
+	use crate::intcode::VmError;
+
+	#[test]
+	fn test_fan_out_and_in() {
+		let echo = assemble("loop: in [x]\n out [x]\n jt #1, #loop\n x: db 0").unwrap();
+		let double = assemble("loop: in [x]\n mul [x], #2, [x]\n out [x]\n jt #1, #loop\n x: db 0").unwrap();
+		let add = assemble("in [a]\n in [b]\n add [a], [b], [a]\n out [a]\n hlt\n a: db 0\n b: db 0").unwrap();
+		let mut topology = Topology::new();
+		let source = topology.add_node(echo, &[5]);
+		let left = topology.add_node(double.clone(), &[]);
+		let right = topology.add_node(double, &[1]);
+		let sum = topology.add_node(add, &[]);
+		topology.connect(source, left);
+		topology.connect(source, right);
+		topology.connect(left, sum);
+		topology.connect(right, sum);
+		let report = topology.run().unwrap();
+		assert_eq!(report.outputs, vec![vec![5], vec![10], vec![2, 10], vec![12]]);
+		assert_eq!(report.halted(), vec![sum]);
+		assert_eq!(report.blocked(), vec![source, left, right]);
+	}
+
+	#[test]
+	fn test_chain_errors() {
+		// Each passes its first input on, then runs its second as an instruction. Only the second gets one, and
+		// 0 isn't an opcode
+		let mut topology = Topology::chain(&vec![3, 0, 4, 0, 3, 6, 99], vec![vec![0], vec![]], false);
+		topology.node_mut(1).push_input(0);
+		let error = NetworkError { node: 1, error: VmError::UnknownOpcode { ip: 6, instruction: 0 } };
+		assert_eq!(topology.run(), Err(error));
+	}
+}
@@ -0,0 +1,258 @@
+use std::any::Any;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use super::disasm::{decode, Instruction};
+use super::{NumType, Opcode};
+
+/// One instruction the VM ran
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+	pub ip: usize,
+	pub instruction: Instruction,
+	/// The values it read, with the modes applied. A jump only reads its condition, where it went is `next`
+	pub reads: Vec<NumType>,
+	/// The address it wrote to and what went there
+	pub write: Option<(usize, NumType)>,
+	/// The ip afterwards
+	pub next: usize,
+}
+
+impl fmt::Display for TraceStep {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut line = format!("{:>5}: {:<28}", self.ip, self.instruction.to_string());
+		if !self.reads.is_empty() {
+			let reads = self.reads.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+			line += &format!(" reads {}", reads.join(", "));
+		}
+		if let Some((address, value)) = self.write {
+			line += &format!(" [{}] = {}", address, value);
+		}
+		if self.next != self.ip + self.instruction.size() {
+			line += &format!(" -> {}", self.next);
+		}
+		f.write_str(line.trim_end())
+	}
+}
+
+/// Gets told about every instruction a VM runs once it's been given one with `IntCodeVM::set_tracer`.
+/// `Any` is so the VM can hand it back as what it was, see `IntCodeVM::take_tracer`
+pub trait Tracer: Any + Send {
+	fn trace(&mut self, step: &TraceStep);
+}
+
+/// Send each step to both
+impl<A: Tracer, B: Tracer> Tracer for (A, B) {
+	fn trace(&mut self, step: &TraceStep) {
+		self.0.trace(step);
+		self.1.trace(step);
+	}
+}
+
+/// Keeps the last few steps, for seeing how a program got where it is
+#[derive(Debug, Clone)]
+pub struct RingBuffer {
+	capacity: usize,
+	steps: VecDeque<TraceStep>,
+}
+
+impl RingBuffer {
+	pub fn new(capacity: usize) -> RingBuffer {
+		RingBuffer { capacity, steps: VecDeque::with_capacity(capacity) }
+	}
+
+	/// Oldest first
+	pub fn steps(&self) -> &VecDeque<TraceStep> {
+		&self.steps
+	}
+}
+
+impl Tracer for RingBuffer {
+	fn trace(&mut self, step: &TraceStep) {
+		if self.capacity == 0 {
+			return;
+		}
+		if self.steps.len() == self.capacity {
+			self.steps.pop_front();
+		}
+		self.steps.push_back(step.clone());
+	}
+}
+
+/// Writes every step to a file, a line each. Writing can't fail part way through a program,
+/// so the first error is kept for `finish`
+#[derive(Debug)]
+pub struct TraceFile {
+	writer: BufWriter<File>,
+	error: Option<io::Error>,
+}
+
+impl TraceFile {
+	pub fn create(path: &Path) -> io::Result<TraceFile> {
+		Ok(TraceFile { writer: BufWriter::new(File::create(path)?), error: None })
+	}
+
+	pub fn finish(mut self) -> io::Result<()> {
+		match self.error.take() {
+			Some(error) => Err(error),
+			None => self.writer.flush(),
+		}
+	}
+}
+
+impl Tracer for TraceFile {
+	fn trace(&mut self, step: &TraceStep) {
+		if self.error.is_none() {
+			if let Err(error) = writeln!(self.writer, "{}", step) {
+				self.error = Some(error);
+			}
+		}
+	}
+}
+
+/// Counts what runs: each address, each opcode, and each trip round a loop
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Profile {
+	pub steps: u64,
+	/// Indexed by address
+	pub by_address: Vec<u64>,
+	pub by_opcode: BTreeMap<&'static str, u64>,
+	/// Backward jumps that were taken, from where to where. Everything in between is a loop body
+	pub loops: BTreeMap<(usize, usize), u64>,
+}
+
+impl Tracer for Profile {
+	fn trace(&mut self, step: &TraceStep) {
+		self.steps += 1;
+		if self.by_address.len() <= step.ip {
+			self.by_address.resize(step.ip + 1, 0);
+		}
+		self.by_address[step.ip] += 1;
+		*self.by_opcode.entry(step.instruction.opcode.mnemonic()).or_default() += 1;
+		let jump = matches!(step.instruction.opcode, Opcode::JumpIfTrue | Opcode::JumpIfFalse);
+		if jump && step.next <= step.ip {
+			*self.loops.entry((step.ip, step.next)).or_default() += 1;
+		}
+	}
+}
+
+impl Profile {
+	/// What ran most, `top` of each. The memory is only for showing the instructions
+	pub fn report(&self, memory: &[NumType], top: usize) -> String {
+		let percent = |count: u64| 100.0 * count as f64 / self.steps.max(1) as f64;
+		let mut lines = vec![format!("{} instructions", self.steps), String::new(), "By opcode:".to_string()];
+		let mut opcodes: Vec<_> = self.by_opcode.iter().collect();
+		opcodes.sort_by(|a, b| b.1.cmp(a.1));
+		for (mnemonic, &count) in opcodes {
+			lines.push(format!("{:>5} {:>12} {:>6.2}%", mnemonic, count, percent(count)));
+		}
+
+		lines.push(String::new());
+		lines.push("Hottest instructions:".to_string());
+		let mut addresses: Vec<(usize, u64)> = self.by_address.iter().copied().enumerate().filter(|(_, c)| *c > 0).collect();
+		addresses.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+		for &(address, count) in addresses.iter().take(top) {
+			let instruction = decode(memory, address).map_or_else(|| "?".to_string(), |i| i.to_string());
+			lines.push(format!("{:>5}: {:<28} {:>12} {:>6.2}%", address, instruction, count, percent(count)));
+		}
+
+		lines.push(String::new());
+		lines.push("Hot loops:".to_string());
+		let mut loops: Vec<_> = self.loops.iter().collect();
+		loops.sort_by(|a, b| b.1.cmp(a.1));
+		for (&(from, to), &count) in loops.iter().take(top) {
+			// Every instruction in the body, counted once per run of it
+			let body: u64 = self.by_address.get(to..=from).map_or(0, |counts| counts.iter().sum());
+			lines.push(format!(
+				"{:>5}..={:<5} {:>12} times, {:>6.2}% of instructions",
+				to,
+				from,
+				count,
+				percent(body)
+			));
+		}
+		if self.loops.is_empty() {
+			lines.push("none".to_string());
+		}
+		lines.join("\n")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::intcode::asm::assemble;
+	use crate::intcode::IntCodeVM;
+
+	const COUNTDOWN: &str = "
+			in [count]
+		loop:
+			out [count]
+			add [count], #-1, [count]
+			jt [count], #loop
+			hlt
+		count:
+			db 0
+	";
+
+	#[test]
+	fn test_ring_buffer() {
+		let mut vm = IntCodeVM::new(assemble(COUNTDOWN).unwrap());
+		vm.set_tracer(RingBuffer::new(2));
+		vm.push_input(3);
+		vm.run_all().unwrap();
+		let ring: RingBuffer = vm.take_tracer().unwrap();
+		let lines: Vec<String> = ring.steps().iter().map(|step| step.to_string()).collect();
+		assert_eq!(
+			lines,
+			vec![
+				"    8: jt [12], #2                  reads 0".to_string(),
+				"   11: hlt".to_string(),
+			]
+		);
+		assert!(vm.take_tracer::<RingBuffer>().is_none());
+	}
+
+	#[test]
+	fn test_trace_steps() {
+		let mut vm = IntCodeVM::new(assemble(COUNTDOWN).unwrap());
+		vm.set_tracer(RingBuffer::new(10));
+		vm.push_input(2);
+		vm.run_all().unwrap();
+		let ring: RingBuffer = vm.take_tracer().unwrap();
+		let steps = ring.steps();
+		assert_eq!(steps[0].write, Some((12, 2)));
+		assert_eq!(steps[1].reads, vec![2]);
+		assert_eq!(steps[2].reads, vec![2, -1]);
+		assert_eq!(steps[2].write, Some((12, 1)));
+		assert_eq!(steps[3].next, 2);
+		assert_eq!(steps.len(), 8);
+
+		// Waiting for input isn't a step
+		let mut vm = IntCodeVM::new(vec![3, 0, 99]);
+		vm.set_tracer(RingBuffer::new(10));
+		vm.run_all().unwrap();
+		assert!(vm.take_tracer::<RingBuffer>().unwrap().steps().is_empty());
+	}
+
+	#[test]
+	fn test_profile() {
+		let memory = assemble(COUNTDOWN).unwrap();
+		let mut vm = IntCodeVM::new(memory.clone());
+		vm.set_tracer((Profile::default(), RingBuffer::new(0)));
+		vm.push_input(10);
+		vm.run_all().unwrap();
+		let (profile, _): (Profile, RingBuffer) = vm.take_tracer().unwrap();
+		assert_eq!(profile.steps, 32);
+		assert_eq!(profile.by_opcode["out"], 10);
+		assert_eq!(profile.by_address[8], 10);
+		// The last time round it falls through
+		assert_eq!(profile.loops, vec![((8, 2), 9)].into_iter().collect());
+		let report = profile.report(&memory, 3);
+		assert!(report.starts_with("32 instructions\n"), "{}", report);
+		assert!(report.contains("    2..=8                9 times,  93.75% of instructions"), "{}", report);
+	}
+}
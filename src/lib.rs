@@ -1,60 +1,192 @@
-#![feature(drain_filter)]
-pub trait SinglePart{
-    fn run(&self) -> String;
-}
+use std::any::Any;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use color_eyre::eyre::{eyre, Result};
 
+pub use answer::{Answer, Answers};
+
+/// A day where the two parts can be solved independently of each other.
+/// The input is parsed once, see `input` for where the text comes from, and handed to each part
 pub trait AoCDay{
-    fn part1(&self) -> String;
-    fn part2(&self) -> String;
+    type Input;
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+    fn part1(&self, input: &Self::Input) -> Result<Answer>;
+    fn part2(&self, input: &Self::Input) -> Result<Answer>;
     /// This method should be implemented if solving both parts together is more efficient than doing them one at a time
-    fn both(&self) -> String {
-        let p1 = self.part1();
-        let p2 = self.part2();
-        format!(
-            "Part1: {}\n\
-            Part2: {}",
-            p1, p2
-        )
-    }
-}
-
-pub mod solutions {
-    // pub mod nineteen01;
-    // pub mod nineteen02;
-    // pub mod nineteen03;
-    // pub mod nineteen04;
-    // pub mod nineteen05;
-    // pub mod nineteen06;
-    // pub mod nineteen07;
-    // pub mod nineteen08;
-    // pub mod nineteen09;
-    // pub mod nineteen10;
-    // pub mod nineteen11;
-    // pub mod nineteen12;
-    // pub mod nineteen13;
-    // pub mod nineteen14;
-    //pub mod nineteen15;
-    //pub mod nineteen16;
-    //pub mod nineteen17;
-    //pub mod nineteen18;
-    //pub mod nineteen19;
-    //pub mod nineteen20;
-    //pub mod nineteen21;
-    //pub mod nineteen22;
-    //pub mod nineteen23;
-    pub mod nineteen25;
-    // pub mod day22;
-    // pub mod day23;
-    // pub mod day24;
-    // pub mod day25;
+    fn both(&self, input: &Self::Input) -> Result<(Answer, Answer)> {
+        Ok((self.part1(input)?, self.part2(input)?))
+    }
+}
+
+/// A day where both parts fall out of the same piece of work.
+/// Some days (looking at you day 25) only have the one part, so the second answer is optional
+pub trait SinglePart{
+    type Input;
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+    fn run(&self, input: &Self::Input) -> Result<(Answer, Option<Answer>)>;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Part {
+    Part1,
+    Part2,
+    #[default]
+    Both,
+}
+
+impl FromStr for Part {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "part1" | "1" => Ok(Self::Part1),
+            "part2" | "2" => Ok(Self::Part2),
+            "both" | "b" => Ok(Self::Both),
+            _ => Err("Unknown"),
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::Part1 => write!(f, "part1"),
+            Part::Part2 => write!(f, "part2"),
+            Part::Both => write!(f, "both"),
+        }
+    }
+}
+
+/// Object safe view over both kinds of day, so they can sit side by side behind a `&dyn Solver`.
+/// The parsed input is type erased, it must be handed back to the same solver that produced it
+pub trait Solver: Sync {
+    fn kind(&self) -> Kind;
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>>;
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Answers>;
+}
+
+/// Which of the two traits a solution was written against
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Kind {
+    /// An `AoCDay`, either part can be run on its own
+    Parts,
+    /// A `SinglePart`, everything comes out of the one run
+    Single,
+}
+
+/// Wraps an `AoCDay` so it can be used as a `Solver`
+pub struct Parts<T>(pub T);
+
+/// Wraps a `SinglePart` so it can be used as a `Solver`
+pub struct Single<T>(pub T);
+
+fn downcast<T: 'static>(input: &dyn Any) -> Result<&T> {
+    input
+        .downcast_ref()
+        .ok_or_else(|| eyre!("Input was parsed by a different solution"))
+}
+
+impl<T> Solver for Parts<T> where T: AoCDay + Sync, T::Input: 'static {
+    fn kind(&self) -> Kind {
+        Kind::Parts
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>> {
+        Ok(Box::new(self.0.parse(input)?))
+    }
+
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Answers> {
+        let input = downcast::<T::Input>(input)?;
+        Ok(match part {
+            Part::Part1 => Answers::new(Some(self.0.part1(input)?), None),
+            Part::Part2 => Answers::new(None, Some(self.0.part2(input)?)),
+            Part::Both => {
+                let (part1, part2) = self.0.both(input)?;
+                Answers::new(Some(part1), Some(part2))
+            }
+        })
+    }
+}
+
+impl<T> Solver for Single<T> where T: SinglePart + Sync, T::Input: 'static {
+    fn kind(&self) -> Kind {
+        Kind::Single
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>> {
+        Ok(Box::new(self.0.parse(input)?))
+    }
+
+    fn solve(&self, input: &dyn Any, part: Part) -> Result<Answers> {
+        let input = downcast::<T::Input>(input)?;
+        let (part1, part2) = self.0.run(input)?;
+        Ok(match part {
+            Part::Part1 => Answers::new(Some(part1), None),
+            Part::Part2 => Answers::new(None, part2),
+            Part::Both => Answers::new(Some(part1), part2),
+        })
+    }
+}
+
+/// Declares the solution modules and registers each of their `SOLUTION`s,
+/// so enabling a day only ever means adding (or uncommenting) it here
+macro_rules! solutions {
+    ($($module:ident),* $(,)?) => {
+        pub mod solutions {
+            $(pub mod $module;)*
+        }
+
+        /// Every solution, in the order they are listed in `solutions!`. See `registry` for looking them up
+        pub static SOLUTIONS: &[registry::Solution] = &[$(solutions::$module::SOLUTION),*];
+    };
+}
+
+solutions! {
+    nineteen01,
+    nineteen02,
+    nineteen03,
+    nineteen04,
+    nineteen05,
+    nineteen06,
+    nineteen07,
+    nineteen08,
+    nineteen09,
+    nineteen10,
+    nineteen11,
+    nineteen12,
+    nineteen13,
+    nineteen14,
+    nineteen15,
+    nineteen16,
+    nineteen17,
+    nineteen18,
+    nineteen19,
+    //nineteen20,
+    nineteen21,
+    nineteen22,
+    nineteen23,
+    nineteen25,
+    day22,
+    day23,
+    day24,
+    day25,
 }
 
 pub use solutions::*;
+pub mod answer;
+pub mod answers;
+pub mod guesses;
+pub mod heap;
+pub mod registry;
+pub mod input;
+pub mod site;
 pub mod intcode;
 pub mod image_layer;
 mod coords;
 mod direction;
 mod map;
-//mod graph;
+mod robot;
+mod graph;
 mod cell;
 //mod torus_map;
\ No newline at end of file
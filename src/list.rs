@@ -0,0 +1,36 @@
+use color_eyre::eyre::Result;
+use structopt::StructOpt;
+
+use aoc_2020::registry;
+use aoc_2020::Kind;
+
+use crate::table::{day_label, Table};
+
+#[derive(StructOpt)]
+pub struct List {
+    /// Only list the solutions for this year
+    #[structopt(long, short)]
+    year: Option<u32>,
+}
+
+impl List {
+    pub fn run(&self) -> Result<String> {
+        let solutions = match self.year {
+            Some(year) => registry::for_year(year),
+            None => registry::all(),
+        };
+        let mut table = Table::new(&["Day", "Name", "Parts"]);
+        for solution in solutions {
+            let parts = match solution.solver.kind() {
+                Kind::Parts => "part 1, part 2",
+                Kind::Single => "both in one run",
+            };
+            table.push(vec![
+                day_label(solution.year, solution.day),
+                solution.name.to_string(),
+                parts.to_string(),
+            ]);
+        }
+        Ok(table.render())
+    }
+}
@@ -1,15 +1,62 @@
-#![feature(drain_filter)]
+use aoc_2020::heap::CountingAllocator;
 use color_eyre::eyre::Result;
 use structopt::StructOpt;
 
+mod arcade;
 mod day;
+mod debug;
+mod disasm;
+mod fetch;
+mod list;
+mod new;
+mod profile;
 mod run;
+mod run_all;
+mod submit;
+mod table;
+mod timing;
+mod verify;
+use arcade::Arcade;
+use debug::Debug;
+use disasm::Disasm;
+use fetch::Fetch;
+use list::List;
+use new::New;
+use profile::Profile;
 use run::Run;
+use run_all::RunAll;
+use submit::Submit;
+use verify::Verify;
+
+// Lets --time say how much memory each part needed
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
 
 #[derive(StructOpt)]
 #[structopt(name = "Advent Of Code")]
 enum Args {
+    /// Run a single day
     Run(Run),
+    /// List every day that has a solution
+    List(List),
+    /// Run every solution, or every solution for a year, against its input
+    RunAll(RunAll),
+    /// Start a new day: a solution module from a template, registered everywhere it needs to be, with its input
+    New(New),
+    /// Download the input for a day into inputs/<year>/, unless it's already there
+    Fetch(Fetch),
+    /// Send the answer to one part of a day to the site, unless it's already known to be wrong
+    Submit(Submit),
+    /// Check every solution, or every solution for a year, against the recorded answers
+    Verify(Verify),
+    /// List an intcode day's program as instructions and data
+    Disasm(Disasm),
+    /// Step through an intcode day's program with breakpoints, watchpoints and a look at memory
+    Debug(Debug),
+    /// Run an intcode day's program on its own and count what ran most
+    Profile(Profile),
+    /// Play 2019 day 13's arcade game, yourself, from a script or left to itself, and watch it
+    Arcade(Arcade),
 }
 
 fn main() -> Result<()> {
@@ -20,6 +67,36 @@ fn main() -> Result<()> {
             let output = run.run()?;
             println!("{}", output)
         }
+        Args::List(list) => {
+            println!("{}", list.run()?)
+        }
+        Args::RunAll(run_all) => {
+            println!("{}", run_all.run()?)
+        }
+        Args::New(new) => {
+            println!("{}", new.run()?)
+        }
+        Args::Fetch(fetch) => {
+            println!("{}", fetch.run()?)
+        }
+        Args::Submit(submit) => {
+            println!("{}", submit.run()?)
+        }
+        Args::Verify(verify) => {
+            println!("{}", verify.run()?)
+        }
+        Args::Disasm(disasm) => {
+            println!("{}", disasm.run()?)
+        }
+        Args::Debug(debug) => {
+            println!("{}", debug.run()?)
+        }
+        Args::Profile(profile) => {
+            println!("{}", profile.run()?)
+        }
+        Args::Arcade(arcade) => {
+            println!("{}", arcade.run()?)
+        }
     }
     Ok(())
 }
@@ -176,7 +176,8 @@ impl TwoDMap {
 		(TwoDMap::from_map(cell_map), curr_lcs)
 	}
 
-	pub fn print(&self){
+	/// Draw it, walls and all
+	pub fn to_image(&self) -> ImageLayer {
 		let mx = self.cell_map
 			.iter()
 			.map(|(&coord, &ct)|{
@@ -192,7 +193,7 @@ impl TwoDMap {
 				}
 			})
 			.collect();
-		println!("{}",ImageLayer::from_hashmap(mx).to_string());
+		ImageLayer::from_hashmap(mx)
 	}
 	
 	pub fn from_output_of_chars
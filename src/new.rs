@@ -0,0 +1,255 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use structopt::StructOpt;
+
+use aoc_2020::site::{self, Site};
+use aoc_2020::{input, registry};
+
+use crate::day::{Day, DayArgs};
+
+const AOC_DAY_TEMPLATE: &str = include_str!("../templates/aoc_day.rs.template");
+const SINGLE_PART_TEMPLATE: &str = include_str!("../templates/single_part.rs.template");
+
+const LIB_RS: &str = "src/lib.rs";
+const BENCH_RS: &str = "benches/bench.rs";
+const SOLUTIONS_DIR: &str = "src/solutions";
+
+/// Modules are named after the year in words then the day, e.g. `nineteen07`
+const YEAR_WORDS: &[(u32, &str)] = &[
+    (2015, "fifteen"),
+    (2016, "sixteen"),
+    (2017, "seventeen"),
+    (2018, "eighteen"),
+    (2019, "nineteen"),
+    (2020, "twenty"),
+    (2021, "twentyone"),
+    (2022, "twentytwo"),
+    (2023, "twentythree"),
+    (2024, "twentyfour"),
+    (2025, "twentyfive"),
+];
+
+#[derive(StructOpt)]
+pub struct New {
+    #[structopt(flatten)]
+    day: DayArgs,
+    /// The puzzle's title, shown by `list`
+    #[structopt(long, short)]
+    name: Option<String>,
+    /// Solve both parts in one go (`SinglePart`) instead of one at a time (`AoCDay`)
+    #[structopt(long)]
+    single: bool,
+}
+
+impl New {
+    pub fn run(&self) -> Result<String> {
+        let day = self.day.day()?;
+        if !Path::new(LIB_RS).is_file() || !Path::new(BENCH_RS).is_file() {
+            return Err(eyre!("Run this from the root of the repository, {} needs updating", LIB_RS));
+        }
+        if let Some(existing) = registry::find(day.year(), day.day()) {
+            return Err(eyre!("{} day {} already has a solution, {:?}", day.year(), day.day(), existing.name));
+        }
+        let module = module_name(day)?;
+        let module_path = Path::new(SOLUTIONS_DIR).join(format!("{}.rs", module));
+        if module_path.exists() {
+            return Err(eyre!("{} already exists", module_path.display()));
+        }
+        let name = self.name.clone().unwrap_or_else(|| format!("Day {}", day.day()));
+
+        // Work everything out before writing anything, so a problem doesn't leave half a day behind
+        let lib = register_module(&read(LIB_RS)?, &module)?;
+        let bench = register_bench(&read(BENCH_RS)?, &module, day, self.single)?;
+        let code = render(if self.single { SINGLE_PART_TEMPLATE } else { AOC_DAY_TEMPLATE }, day, &name);
+
+        let mut changed = vec![write(&module_path, &code)?, write(Path::new(LIB_RS), &lib)?, write(Path::new(BENCH_RS), &bench)?];
+        let example = example_path(day);
+        if !example.exists() {
+            changed.push(write(&example, "")?);
+        }
+        changed.extend(create_input(day)?);
+        Ok(changed.iter().map(|line| line.to_string()).collect::<Vec<_>>().join("\n"))
+    }
+}
+
+pub fn module_name(day: Day) -> Result<String> {
+    let word = YEAR_WORDS
+        .iter()
+        .find(|(year, _)| *year == day.year())
+        .map(|(_, word)| word)
+        .ok_or_else(|| eyre!("There's no module name for {} yet, add it to YEAR_WORDS", day.year()))?;
+    Ok(format!("{}{:02}", word, day.day()))
+}
+
+/// Which puzzle a module in `solutions!` is for. The first 2020 days were called `dayNN`
+fn module_day(module: &str) -> Option<(u32, u32)> {
+    let split = module.len().checked_sub(2)?;
+    let (word, day) = module.split_at(split);
+    let day = day.parse().ok()?;
+    let year = match word {
+        "day" => 2020,
+        word => YEAR_WORDS.iter().find(|(_, w)| *w == word)?.0,
+    };
+    Some((year, day))
+}
+
+/// Add a module to the `solutions!` list in lib.rs, keeping it in year then day order
+pub fn register_module(lib: &str, module: &str) -> Result<String> {
+    let key = module_day(module).ok_or_else(|| eyre!("{} isn't a solution module name", module))?;
+    let start = lib.find("solutions! {").ok_or_else(|| eyre!("Couldn't find solutions! in {}", LIB_RS))?;
+    let mut lines: Vec<&str> = lib.lines().collect();
+    let first = lib[..start].lines().count() + 1;
+    let last = first + lines[first..].iter().position(|l| l.trim() == "}").ok_or_else(|| eyre!("solutions! isn't closed"))?;
+    let entry = |line: &str| line.trim().trim_start_matches("//").trim_end_matches(',').trim().to_string();
+    if lines[first..last].iter().any(|line| entry(line) == module) {
+        return Err(eyre!("{} is already listed in solutions!, it might just need uncommenting", module));
+    }
+    let position = (first..last)
+        .find(|&i| module_day(&entry(lines[i])).map_or(false, |other| other > key))
+        .unwrap_or(last);
+    let line = format!("    {},", module);
+    lines.insert(position, &line);
+    let ending = if lib.ends_with('\n') { "\n" } else { "" };
+    Ok(lines.join("\n") + ending)
+}
+
+/// Add a benchmark for a new day to benches/bench.rs and to its criterion group
+pub fn register_bench(bench: &str, module: &str, day: Day, single: bool) -> Result<String> {
+    if bench.contains(&format!("fn {}(", module)) {
+        return Err(eyre!("{} already has a benchmark called {}", BENCH_RS, module));
+    }
+    let group = bench.find("criterion_group!(").ok_or_else(|| eyre!("Couldn't find criterion_group! in {}", BENCH_RS))?;
+    let group_end = group + bench[group..].find(");").ok_or_else(|| eyre!("criterion_group! isn't closed"))?;
+    let label = format!("{}-{:02}", day.year(), day.day());
+    let runs = if single {
+        format!("\tc.bench_function(\"{} Only Part\", |b| b.iter(|| code.run(&input)));\n", label)
+    } else {
+        format!(
+            "\tc.bench_function(\"{0} Part 1\", |b| b.iter(|| code.part1(&input)));\n\
+             \tc.bench_function(\"{0} Part 2\", |b| b.iter(|| code.part2(&input)));\n",
+            label
+        )
+    };
+    let function = format!(
+        "pub fn {module}(c: &mut Criterion) {{\n\
+         \tuse aoc_2020::{module}::Code;\n\
+         \tlet code: Code = Code {{}};\n\
+         \tlet input = code.parse(&load({year}, {day})).unwrap();\n\
+         {runs}}}\n\n",
+        module = module,
+        year = day.year(),
+        day = day.day(),
+        runs = runs
+    );
+    Ok(format!(
+        "{}{}{}, {}{}",
+        &bench[..group],
+        function,
+        bench[group..group_end].trim_end(),
+        module,
+        &bench[group_end..]
+    ))
+}
+
+fn render(template: &str, day: Day, name: &str) -> String {
+    template
+        .replace("{{year}}", &day.year().to_string())
+        .replace("{{day}}", &day.day().to_string())
+        .replace("{{name}}", &name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn example_path(day: Day) -> PathBuf {
+    Path::new(input::INPUT_DIR)
+        .join(day.year().to_string())
+        .join(format!("Day{}-eg-a.txt", day.day()))
+}
+
+/// Download the input if we're logged in, otherwise leave an empty file to paste it into
+fn create_input(day: Day) -> Result<Option<String>> {
+    let path = input::default_path(day.year(), day.day());
+    if path.exists() {
+        return Ok(None);
+    }
+    if site::session_token()?.is_some() {
+        match Site::from_env().and_then(|site| site.fetch(day.year(), day.day())) {
+            Ok(path) => return Ok(Some(format!("downloaded {}", path.display()))),
+            Err(e) => eprintln!("Couldn't download the input, leaving an empty file instead: {}", e),
+        }
+    }
+    write(&path, "").map(Some)
+}
+
+fn read(path: &str) -> Result<String> {
+    fs::read_to_string(path).wrap_err_with(|| format!("Couldn't read {}", path))
+}
+
+fn write(path: &Path, contents: &str) -> Result<String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).wrap_err_with(|| format!("Couldn't create {}", parent.display()))?;
+    }
+    fs::write(path, contents).wrap_err_with(|| format!("Couldn't write {}", path.display()))?;
+    Ok(format!("wrote {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(year: u32, day: u32) -> Day {
+        Day::new(year, day).unwrap()
+    }
+
+    #[test]
+    fn test_module_name() {
+        assert_eq!(module_name(day(2019, 7)).unwrap(), "nineteen07");
+        assert_eq!(module_name(day(2020, 1)).unwrap(), "twenty01");
+        assert_eq!(module_day("twenty01"), Some((2020, 1)));
+        assert_eq!(module_day("day22"), Some((2020, 22)));
+        assert_eq!(module_day("nineteen25"), Some((2019, 25)));
+        assert_eq!(module_day("torus_map"), None);
+    }
+
+    const LIB: &str = "mod a;\n\nsolutions! {\n    nineteen01,\n    //nineteen20,\n    nineteen23,\n    nineteen25,\n    day22,\n    day25,\n}\n\npub use solutions::*;\n";
+
+    const BENCH: &str = "pub fn twenty25(c: &mut Criterion) {\n}\n\ncriterion_group!(benches,nineteen01,\n\t twenty25);\ncriterion_main!(benches);";
+
+    #[test]
+    fn test_register_module() {
+        let lib = LIB;
+        let updated = register_module(lib, "nineteen24").unwrap();
+        assert!(updated.contains("    nineteen23,\n    nineteen24,\n    nineteen25,\n"));
+        let updated = register_module(lib, "twenty01").unwrap();
+        assert!(updated.contains("    nineteen25,\n    twenty01,\n    day22,\n"));
+        let updated = register_module(lib, "twentyone01").unwrap();
+        assert!(updated.contains("    day25,\n    twentyone01,\n}"));
+        assert_eq!(updated.lines().count(), lib.lines().count() + 1);
+        // Commented out, but still there
+        assert!(register_module(lib, "nineteen20").is_err());
+        assert!(register_module(lib, "nineteen01").is_err());
+    }
+
+    #[test]
+    fn test_register_bench() {
+        let bench = BENCH;
+        let updated = register_bench(bench, "twenty01", day(2020, 1), false).unwrap();
+        assert!(updated.contains("pub fn twenty01(c: &mut Criterion) {\n\tuse aoc_2020::twenty01::Code;"));
+        assert!(updated.contains("\"2020-01 Part 2\""));
+        assert!(updated.contains("}\n\npub fn twenty01(c: &mut Criterion) {\n"));
+        assert!(updated.ends_with("\t twenty25, twenty01);\ncriterion_main!(benches);"));
+        let updated = register_bench(bench, "twenty01", day(2020, 1), true).unwrap();
+        assert!(updated.contains("\"2020-01 Only Part\", |b| b.iter(|| code.run(&input))"));
+        assert!(register_bench(bench, "twenty25", day(2020, 25), false).is_err());
+    }
+
+    #[test]
+    fn test_render() {
+        for template in &[AOC_DAY_TEMPLATE, SINGLE_PART_TEMPLATE] {
+            let code = render(template, day(2020, 1), "Report \"Repair\"");
+            assert!(!code.contains("{{"));
+            assert!(code.contains("Solution::new(2020, 1, \"Report \\\"Repair\\\"\""));
+            assert!(code.contains("inputs/2020/Day1-eg-a.txt"));
+        }
+    }
+}
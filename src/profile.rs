@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use structopt::StructOpt;
+
+use aoc_2020::input;
+use aoc_2020::intcode::trace::{self, TraceFile};
+use aoc_2020::intcode::{memory_from_str, IntCodeVM, NumType, MAX_MEMORY};
+
+use crate::day::DayArgs;
+
+#[derive(StructOpt)]
+pub struct Profile {
+    #[structopt(flatten)]
+    day: DayArgs,
+    /// Where to read the program from. Defaults to the day's input
+    #[structopt(long, short, parse(from_os_str))]
+    input: Option<PathBuf>,
+    /// A number to give the program as input, can be given more than once
+    #[structopt(long, short, number_of_values = 1, allow_hyphen_values = true)]
+    value: Vec<NumType>,
+    /// A line of text to give the program as input, after any --value
+    #[structopt(long, number_of_values = 1)]
+    send: Vec<String>,
+    /// Change memory before it starts, as `address=value`, e.g. `--set 1=12` for 2019 day 2
+    #[structopt(long, number_of_values = 1)]
+    set: Vec<String>,
+    /// Also write every instruction run to this file
+    #[structopt(long, parse(from_os_str))]
+    trace: Option<PathBuf>,
+    /// How many of the hottest instructions and loops to show
+    #[structopt(long, default_value = "10")]
+    top: usize,
+}
+
+impl Profile {
+    pub fn run(&self) -> Result<String> {
+        let day = self.day.day()?;
+        let source = input::load(self.input.as_deref(), day.year(), day.day())?;
+        let mut memory = memory_from_str(&source).wrap_err("That isn't an intcode program")?;
+        for set in &self.set {
+            let (address, value) = parse_set(set)?;
+            if memory.len() <= address {
+                memory.resize(address + 1, 0);
+            }
+            memory[address] = value;
+        }
+
+        let mut vm = IntCodeVM::new(memory.clone());
+        self.value.iter().for_each(|&v| vm.push_input(v));
+        for line in &self.send {
+            line.chars().chain(Some('\n')).for_each(|c| vm.push_input(c as NumType));
+        }
+        let ran = match &self.trace {
+            Some(path) => {
+                let file = TraceFile::create(path).wrap_err_with(|| format!("Couldn't create {}", path.display()))?;
+                vm.set_tracer((trace::Profile::default(), file));
+                let ran = vm.run_all();
+                let (profile, file): (trace::Profile, TraceFile) = vm.take_tracer().expect("the tracer was just set");
+                file.finish().wrap_err_with(|| format!("Couldn't write the trace to {}", path.display()))?;
+                ran.map(|_| profile)
+            }
+            None => {
+                vm.set_tracer(trace::Profile::default());
+                vm.run_all().map(|_| vm.take_tracer().expect("the tracer was just set"))
+            }
+        };
+        let profile = ran.wrap_err("The program failed")?;
+
+        let ending = if vm.is_waiting() {
+            format!("It stopped at {} waiting for more input", vm.register)
+        } else {
+            "It halted".to_string()
+        };
+        Ok(format!(
+            "{}, giving {} output value(s)\n\n{}",
+            ending,
+            vm.output().len(),
+            profile.report(&memory, self.top)
+        ))
+    }
+}
+
+fn parse_set(set: &str) -> Result<(usize, NumType)> {
+    let (address, value) = set.split_once('=').ok_or_else(|| eyre!("{:?} should be address=value", set))?;
+    let address: usize = address.trim().parse().wrap_err_with(|| format!("{:?} isn't an address", address))?;
+    if address >= MAX_MEMORY {
+        return Err(eyre!("{} is past the end of memory, addresses go up to {}", address, MAX_MEMORY - 1));
+    }
+    let value = value.trim().parse().wrap_err_with(|| format!("{:?} isn't a number", value))?;
+    Ok((address, value))
+}
@@ -0,0 +1,160 @@
+use std::any::Any;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Result};
+
+use crate::{heap, Answer, Answers, Kind, Part, Solver, SOLUTIONS};
+
+/// A solution along with which puzzle it solves.
+/// Each module in `solutions` exposes one of these as `SOLUTION`, and lists itself in `solutions!`
+pub struct Solution {
+	pub year: u32,
+	pub day: u32,
+	pub name: &'static str,
+	pub solver: &'static dyn Solver,
+}
+
+/// How long one step of solving a day took, and how much the heap grew while it ran.
+/// The heap is only counted when the binary uses `heap::CountingAllocator`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+	pub label: &'static str,
+	pub time: Duration,
+	pub peak_heap: usize,
+}
+
+/// What happened to each part of a day. They're kept apart so one broken part doesn't hide the other
+pub struct PartResults {
+	pub part1: Result<Option<Answer>>,
+	pub part2: Result<Option<Answer>>,
+}
+
+impl Solution {
+	pub const fn new(year: u32, day: u32, name: &'static str, solver: &'static dyn Solver) -> Solution {
+		Solution { year, day, name, solver }
+	}
+
+	/// Solve a day one step at a time, timing parsing and each part separately.
+	/// Days that solve both parts together get one step for the pair
+	pub fn timed(&self, input: &str, part: Part) -> Result<(Answers, Vec<Step>)> {
+		let mut steps = Vec::new();
+		let parsed = step(&mut steps, "Parse", || self.solver.parse(input))?;
+		let answers = match (self.solver.kind(), part) {
+			(Kind::Parts, Part::Both) => {
+				let part1 = step(&mut steps, "Part 1", || self.solver.solve(parsed.as_ref(), Part::Part1))?;
+				let part2 = step(&mut steps, "Part 2", || self.solver.solve(parsed.as_ref(), Part::Part2))?;
+				Answers::new(part1.part1, part2.part2)
+			}
+			(Kind::Parts, Part::Part1) => step(&mut steps, "Part 1", || self.solver.solve(parsed.as_ref(), part))?,
+			(Kind::Parts, Part::Part2) => step(&mut steps, "Part 2", || self.solver.solve(parsed.as_ref(), part))?,
+			(Kind::Single, _) => step(&mut steps, BOTH_PARTS, || self.solver.solve(parsed.as_ref(), part))?,
+		};
+		Ok((answers, steps))
+	}
+
+	/// Run both parts on input that has already been through `solver.parse`
+	pub fn run_parts(&self, parsed: &dyn Any) -> PartResults {
+		match self.solver.kind() {
+			Kind::Parts => PartResults {
+				part1: self.solver.solve(parsed, Part::Part1).map(|a| a.part1),
+				part2: self.solver.solve(parsed, Part::Part2).map(|a| a.part2),
+			},
+			Kind::Single => match self.solver.solve(parsed, Part::Both) {
+				Ok(answers) => PartResults {
+					part1: Ok(answers.part1),
+					part2: Ok(answers.part2),
+				},
+				Err(e) => PartResults {
+					part2: Err(eyre!("Solved together with part 1, see there")),
+					part1: Err(e),
+				},
+			},
+		}
+	}
+}
+
+/// The label of the step for days that solve both parts together
+pub const BOTH_PARTS: &str = "Both parts";
+
+fn step<T>(steps: &mut Vec<Step>, label: &'static str, f: impl FnOnce() -> T) -> T {
+	let start = Instant::now();
+	let (result, peak_heap) = heap::peak_during(f);
+	steps.push(Step { label, time: start.elapsed(), peak_heap });
+	result
+}
+
+impl fmt::Debug for Solution {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Solution")
+			.field("year", &self.year)
+			.field("day", &self.day)
+			.field("name", &self.name)
+			.finish()
+	}
+}
+
+/// Every registered solution, ordered by year then day
+pub fn all() -> Vec<&'static Solution> {
+	let mut all: Vec<&'static Solution> = SOLUTIONS.iter().collect();
+	all.sort_by_key(|s| (s.year, s.day));
+	all
+}
+
+/// The solutions for a single year, in day order
+pub fn for_year(year: u32) -> Vec<&'static Solution> {
+	all().into_iter().filter(|s| s.year == year).collect()
+}
+
+/// The years that have at least one solution
+pub fn years() -> Vec<u32> {
+	let mut years: Vec<u32> = SOLUTIONS.iter().map(|s| s.year).collect();
+	years.sort_unstable();
+	years.dedup();
+	years
+}
+
+pub fn find(year: u32, day: u32) -> Option<&'static Solution> {
+	SOLUTIONS.iter().find(|s| s.year == year && s.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_no_duplicates() {
+		let all = all();
+		for pair in all.windows(2) {
+			assert_ne!((pair[0].year, pair[0].day), (pair[1].year, pair[1].day), "{:?} is registered twice", pair[0]);
+		}
+		assert!(all.iter().all(|s| (1..=25).contains(&s.day)));
+	}
+
+	#[test]
+	fn test_find() {
+		assert_eq!(find(2019, 1).unwrap().name, "The Tyranny of the Rocket Equation");
+		assert_eq!(find(2020, 25).unwrap().name, "Combo Breaker");
+		// Still waiting on part 2
+		assert!(find(2019, 20).is_none());
+		assert!(find(2018, 1).is_none());
+	}
+
+	#[test]
+	fn test_years() {
+		assert_eq!(years(), vec![2019, 2020]);
+		assert!(for_year(2020).iter().all(|s| s.year == 2020));
+	}
+
+	#[test]
+	fn test_timed() {
+		let (answers, steps) = find(2019, 1).unwrap().timed("12\n14\n", Part::Both).unwrap();
+		assert_eq!(answers, Answers::new(Some(Answer::Int(4)), Some(Answer::Int(4))));
+		assert_eq!(steps.iter().map(|s| s.label).collect::<Vec<_>>(), vec!["Parse", "Part 1", "Part 2"]);
+		let (_, steps) = find(2019, 1).unwrap().timed("12\n", Part::Part2).unwrap();
+		assert_eq!(steps.iter().map(|s| s.label).collect::<Vec<_>>(), vec!["Parse", "Part 2"]);
+		let (answers, steps) = find(2020, 25).unwrap().timed("5764801\n17807724\n", Part::Both).unwrap();
+		assert_eq!(answers.part1, Some(Answer::Int(14897079)));
+		assert_eq!(steps.iter().map(|s| s.label).collect::<Vec<_>>(), vec!["Parse", BOTH_PARTS]);
+	}
+}
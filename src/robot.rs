@@ -0,0 +1,378 @@
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use color_eyre::eyre::{eyre, Result};
+
+use crate::cell::CellType;
+use crate::coords::Coords;
+use crate::direction::Direction;
+use crate::intcode::{IntCodeVM, NumType};
+use crate::map::TwoDMap;
+
+const DIRECTIONS: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+
+/// What happened when a robot tried to take a step
+#[derive(Clone, Debug, PartialEq)]
+pub enum Step<C> {
+	/// It's there now, and this is what it found
+	Moved(C),
+	/// Something's in the way, a wall or a closed door
+	Blocked,
+	/// It saw what's there but got sent back, like day 25's pressure-sensitive floor
+	Bounced(C),
+}
+
+/// How to drive a robot that's told where to go, like day 15's repair droid or day 25's droid on the ship.
+/// This is all a new puzzle has to write: `Explorer` does the rest
+pub trait Protocol {
+	/// What the robot finds at each place it gets to
+	type Cell: Clone;
+
+	/// Where it starts, once it's had a look around
+	fn start(&mut self) -> Result<Self::Cell>;
+
+	/// Try to go one step
+	fn step(&mut self, direction: Direction) -> Result<Step<Self::Cell>>;
+
+	/// Which ways it could go from where it is, if it can tell without trying. Otherwise every way gets tried
+	fn exits(&mut self) -> Option<Vec<Direction>> {
+		None
+	}
+
+	/// How a place is drawn on a `TwoDMap`
+	fn cell_type(&self, _cell: &Self::Cell) -> CellType {
+		CellType::Space
+	}
+}
+
+/// Maps everywhere a robot can get to: it keeps going to the nearest place there's still a way to try from,
+/// found by a breadth first search through what's known so far, and tries it.
+///
+/// Places don't have to line up like a real grid. If a way out leads somewhere that's already taken by a place
+/// with no way back, what's through it gets put further along instead (day 25's ship does this)
+pub struct Explorer<P: Protocol> {
+	protocol: P,
+	position: Coords,
+	cells: BTreeMap<Coords, P::Cell>,
+	/// Where it can't go, walls and anywhere it was sent back from
+	blocked: BTreeSet<Coords>,
+	/// Which ways there are out of each place. Two places side by side needn't have a way between them
+	exits: BTreeMap<Coords, Vec<Direction>>,
+	/// Where each way out it knows about goes
+	doors: BTreeMap<(Coords, Direction), Coords>,
+	untried: BTreeMap<Coords, Vec<Direction>>,
+}
+
+impl<P: Protocol> Explorer<P> {
+	pub fn new(protocol: P) -> Explorer<P> {
+		Explorer {
+			protocol,
+			position: Coords::default(),
+			cells: BTreeMap::new(),
+			blocked: BTreeSet::new(),
+			exits: BTreeMap::new(),
+			doors: BTreeMap::new(),
+			untried: BTreeMap::new(),
+		}
+	}
+
+	pub fn cells(&self) -> &BTreeMap<Coords, P::Cell> {
+		&self.cells
+	}
+
+	/// For doing more with the robot than walking it around, like picking things up
+	pub fn protocol_mut(&mut self) -> &mut P {
+		&mut self.protocol
+	}
+
+	pub fn explore(&mut self) -> Result<()> {
+		if self.cells.is_empty() {
+			let cell = self.protocol.start()?;
+			self.arrive(cell);
+		}
+		while let Some(route) = self.route_where(|explorer, coords| explorer.untried.contains_key(coords)) {
+			self.walk(&route)?;
+			let direction = self.untried.get_mut(&self.position).and_then(|ways| ways.pop()).expect("it was untried");
+			if self.untried[&self.position].is_empty() {
+				self.untried.remove(&self.position);
+			}
+			let mut next = self.position.move_direction(direction);
+			if self.doors.contains_key(&(self.position, direction)) || self.blocked.contains(&next) {
+				continue;
+			}
+			while self.cells.contains_key(&next) || self.blocked.contains(&next) {
+				next = next.move_direction(direction);
+			}
+			match self.protocol.step(direction)? {
+				Step::Moved(cell) => {
+					self.link(self.position, direction, next);
+					self.position = next;
+					self.arrive(cell);
+				}
+				Step::Blocked => {
+					self.blocked.insert(next);
+				}
+				Step::Bounced(cell) => {
+					// Only one way, there's no coming back from somewhere it never got to
+					self.doors.insert((self.position, direction), next);
+					self.cells.insert(next, cell);
+					self.blocked.insert(next);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Follow a route through places it's already been
+	pub fn walk(&mut self, route: &[Direction]) -> Result<()> {
+		for &direction in route {
+			match self.protocol.step(direction)? {
+				Step::Moved(_) if self.doors.contains_key(&(self.position, direction)) => {
+					self.position = self.doors[&(self.position, direction)]
+				}
+				_ => return Err(eyre!("The robot couldn't go {:?} from {:?} this time", direction, self.position)),
+			}
+		}
+		Ok(())
+	}
+
+	/// The shortest way from where it is to somewhere it's been
+	pub fn route_to(&self, to: Coords) -> Option<Vec<Direction>> {
+		self.route_where(|_, &coords| coords == to)
+	}
+
+	/// Which way out of `from` goes to `to`, including onto somewhere it was sent back from
+	pub fn door_between(&self, from: Coords, to: Coords) -> Option<Direction> {
+		DIRECTIONS.iter().copied().find(|&direction| self.doors.get(&(from, direction)) == Some(&to))
+	}
+
+	/// How many steps it is from `from` to everywhere that can be reached from there
+	pub fn distances(&self, from: Coords) -> BTreeMap<Coords, usize> {
+		let mut distances = BTreeMap::new();
+		distances.insert(from, 0);
+		let mut queue = VecDeque::new();
+		queue.push_back(from);
+		while let Some(current) = queue.pop_front() {
+			let distance = distances[&current];
+			for next in self.neighbours(current) {
+				if let Entry::Vacant(entry) = distances.entry(next) {
+					entry.insert(distance + 1);
+					queue.push_back(next);
+				}
+			}
+		}
+		distances
+	}
+
+	/// Everywhere it's been, walls around it and all (a way out that isn't there is drawn as a wall too, unless
+	/// somewhere's been found on the other side). Anywhere it didn't look is left off
+	pub fn to_map(&self) -> TwoDMap {
+		let mut cell_map: BTreeMap<Coords, CellType> =
+			self.blocked.iter().map(|&coords| (coords, CellType::NormalBarrier)).collect();
+		for (coords, exits) in &self.exits {
+			for &direction in DIRECTIONS.iter().filter(|direction| !exits.contains(direction)) {
+				cell_map.insert(coords.move_direction(direction), CellType::NormalBarrier);
+			}
+		}
+		for (&coords, cell) in &self.cells {
+			cell_map.insert(coords, self.protocol.cell_type(cell));
+		}
+		TwoDMap::from_map(cell_map)
+	}
+
+	/// Where it can get to in one step from somewhere it's been
+	fn neighbours(&self, coords: Coords) -> impl Iterator<Item = Coords> + '_ {
+		self.doors_from(coords).map(|(_, next)| next)
+	}
+
+	fn doors_from(&self, coords: Coords) -> impl Iterator<Item = (Direction, Coords)> + '_ {
+		DIRECTIONS.iter().filter_map(move |&direction| {
+			self.doors.get(&(coords, direction)).filter(|next| !self.blocked.contains(next)).map(|&next| (direction, next))
+		})
+	}
+
+	/// Doors are taken to go both ways
+	fn link(&mut self, from: Coords, direction: Direction, to: Coords) {
+		self.doors.insert((from, direction), to);
+		self.doors.insert((to, direction.opposite()), from);
+	}
+
+	fn arrive(&mut self, cell: P::Cell) {
+		self.cells.insert(self.position, cell);
+		let ways = self.protocol.exits().unwrap_or_else(|| DIRECTIONS.to_vec());
+		// A place next door with a way back is taken to be where this way goes
+		for &direction in &ways {
+			let next = self.position.move_direction(direction);
+			let back = self.exits.get(&next).is_some_and(|exits| exits.contains(&direction.opposite()));
+			if back && !self.doors.contains_key(&(next, direction.opposite())) {
+				self.link(self.position, direction, next);
+			}
+		}
+		// Popped from the back, so they're tried in the order given
+		let mut untried: Vec<Direction> = ways
+			.iter()
+			.copied()
+			.filter(|&direction| {
+				!self.doors.contains_key(&(self.position, direction))
+					&& !self.blocked.contains(&self.position.move_direction(direction))
+			})
+			.collect();
+		untried.reverse();
+		if !untried.is_empty() {
+			self.untried.insert(self.position, untried);
+		}
+		self.exits.insert(self.position, ways);
+	}
+
+	/// Breadth first from where it is through open places to the nearest one that's wanted
+	fn route_where<F: Fn(&Self, &Coords) -> bool>(&self, wanted: F) -> Option<Vec<Direction>> {
+		let mut came_from: BTreeMap<Coords, (Coords, Direction)> = BTreeMap::new();
+		let mut queue = VecDeque::new();
+		queue.push_back(self.position);
+		while let Some(current) = queue.pop_front() {
+			if wanted(self, &current) {
+				let mut route = Vec::new();
+				let mut back = current;
+				while let Some(&(previous, direction)) = came_from.get(&back) {
+					route.push(direction);
+					back = previous;
+				}
+				route.reverse();
+				return Some(route);
+			}
+			for (direction, next) in self.doors_from(current) {
+				if next != self.position && !came_from.contains_key(&next) {
+					came_from.insert(next, (current, direction));
+					queue.push_back(next);
+				}
+			}
+		}
+		None
+	}
+}
+
+/// Which way a robot that steers itself turns
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Turn {
+	Left,
+	Right,
+}
+
+/// How to drive a robot that steers itself, like day 11's painter: each step its program is told about the
+/// place it's on, then says what to leave there and which way to turn before moving forward
+pub trait Pilot {
+	type Cell: Clone;
+
+	/// What the program's told about where the robot is, `None` if nothing's been left there
+	fn sense(&self, cell: Option<&Self::Cell>) -> NumType;
+
+	/// Everything the program output in a step, as what to leave behind and the way to turn
+	fn act(&mut self, output: &[NumType]) -> Result<(Self::Cell, Turn)>;
+}
+
+/// Runs a `Pilot`'s program until it halts, keeping what it leaves where
+pub struct Rover<P: Pilot> {
+	pilot: P,
+	vm: IntCodeVM,
+	position: Coords,
+	facing: Direction,
+	cells: BTreeMap<Coords, P::Cell>,
+}
+
+impl<P: Pilot> Rover<P> {
+	pub fn new(pilot: P, vm: IntCodeVM, facing: Direction) -> Rover<P> {
+		Rover { pilot, vm, position: Coords::default(), facing, cells: BTreeMap::new() }
+	}
+
+	/// Leave something before it starts, like day 11's white panel
+	pub fn set_cell(&mut self, coords: Coords, cell: P::Cell) {
+		self.cells.insert(coords, cell);
+	}
+
+	pub fn cells(&self) -> &BTreeMap<Coords, P::Cell> {
+		&self.cells
+	}
+
+	pub fn into_cells(self) -> BTreeMap<Coords, P::Cell> {
+		self.cells
+	}
+
+	pub fn run(&mut self) -> Result<()> {
+		while !self.vm.is_stopped() {
+			self.vm.push_input(self.pilot.sense(self.cells.get(&self.position)));
+			self.vm.run_all()?;
+			let output: Vec<NumType> = self.vm.output().take().into();
+			if output.is_empty() {
+				continue;
+			}
+			let (cell, turn) = self.pilot.act(&output)?;
+			self.cells.insert(self.position, cell);
+			self.facing = match turn {
+				Turn::Left => self.facing.turn_left(),
+				Turn::Right => self.facing.turn_right(),
+			};
+			self.position = self.position.move_direction(self.facing);
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// A maze drawn out, which a fake robot walks around
+	struct Maze {
+		rows: Vec<&'static [u8]>,
+		at: Coords,
+	}
+
+	impl Maze {
+		fn cell(&self, coords: Coords) -> u8 {
+			self.rows[(coords.y + 1) as usize][(coords.x + 1) as usize]
+		}
+	}
+
+	impl Protocol for Maze {
+		type Cell = u8;
+
+		fn start(&mut self) -> Result<u8> {
+			Ok(self.cell(self.at))
+		}
+
+		fn step(&mut self, direction: Direction) -> Result<Step<u8>> {
+			let next = self.at.move_direction(direction);
+			Ok(match self.cell(next) {
+				b'#' => Step::Blocked,
+				b'^' => Step::Bounced(b'^'),
+				cell => {
+					self.at = next;
+					Step::Moved(cell)
+				}
+			})
+		}
+
+		fn cell_type(&self, &cell: &u8) -> CellType {
+			if cell == b'.' { CellType::Space } else { CellType::Goal(cell as char) }
+		}
+	}
+
+	#[test]
+	fn test_explore_maze() {
+		let rows: Vec<&'static [u8]> = vec![b"#######", b"#...#^#", b"#.#.#.#", b"#.#...#", b"#X#####", b"#######"];
+		let mut explorer = Explorer::new(Maze { rows, at: Coords::default() });
+		explorer.explore().unwrap();
+		let cells = explorer.cells();
+		assert_eq!(cells.len(), 12);
+		assert_eq!(cells[&Coords { x: 4, y: 0 }], b'^');
+		// The bounced off cell's on the map, but nothing goes through it
+		let goal = Coords { x: 0, y: 3 };
+		let distances = explorer.distances(goal);
+		assert_eq!(distances[&Coords { x: 4, y: 1 }], 10);
+		assert_eq!(distances.len(), 11);
+		assert!(!distances.contains_key(&Coords { x: 4, y: 0 }));
+		let map = explorer.to_map();
+		assert_eq!(map.cell_map()[&goal], CellType::Goal('X'));
+		assert_eq!(map.cell_map()[&Coords { x: 3, y: 0 }], CellType::NormalBarrier);
+	}
+}
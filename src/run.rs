@@ -1,65 +1,70 @@
-use std::fmt::Display;
-use std::str::FromStr;
+use std::path::{Path, PathBuf};
 
 use color_eyre::eyre::Result;
 use structopt::StructOpt;
 
-use crate::day::Day;
+use aoc_2020::{input, Answer, Answers, Part};
+
+use crate::day::{Day, DayArgs};
+use crate::timing;
 
 #[derive(StructOpt)]
 pub struct Run {
-    /// Problem day to run
-    day: Day,
+    #[structopt(flatten)]
+    day: DayArgs,
     /// Part to run
     #[structopt(long, short, default_value)]
     part: Part,
+    /// Where to read the puzzle input from, `-` for stdin.
+    /// Defaults to inputs/<year>/Day<N>.txt, downloading it if it's not there
+    #[structopt(long, short, parse(from_os_str))]
+    input: Option<PathBuf>,
+    /// Say how long parsing and each part took, and how much the heap grew during each
+    #[structopt(long, short)]
+    time: bool,
+    /// Run it this many times and report the min, mean and median of each step. Implies --time
+    #[structopt(long, default_value = "1")]
+    repeat: usize,
 }
 
-#[derive(StructOpt)]
-enum Part {
-    Part1,
-    Part2,
-    Both,
-}
-
-impl FromStr for Part {
-    type Err = &'static str;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "part1" | "1" => Ok(Self::Part1),
-            "part2" | "2" => Ok(Self::Part2),
-            "both" | "b" => Ok(Self::Both),
-            _ => Err("Unknown"),
+impl Run {
+    pub fn run(&self) -> Result<String> {
+        let day = self.day.day()?;
+        if !self.time && self.repeat == 1 {
+            return Ok(format_answers(&solve(day, self.part, self.input.as_deref())?));
         }
+        let solution = day.get_code()?;
+        let input = input::load(self.input.as_deref(), day.year(), day.day())?;
+        let (answers, summaries) = timing::repeat(solution, &input, self.part, self.repeat)?;
+        Ok(format!(
+            "{}\n\n{}",
+            format_answers(&answers),
+            timing::table(&summaries, self.repeat).render()
+        ))
     }
 }
 
-impl Default for Part {
-    fn default() -> Self {
-        Self::Both
-    }
+/// Load the input for a day and run the part(s) asked for on it
+pub fn solve(day: Day, part: Part, input: Option<&Path>) -> Result<Answers> {
+    let solution = day.get_code()?;
+    let input = input::load(input, day.year(), day.day())?;
+    let parsed = solution.solver.parse(&input)?;
+    solution.solver.solve(parsed.as_ref(), part)
 }
 
-impl Display for Part {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Part::Part1 => write!(f, "part1"),
-            Part::Part2 => write!(f, "part2"),
-            Part::Both => write!(f, "both"),
-        }
-    }
+/// Lay out each answer the same way, answers that need several lines start on their own line
+pub fn format_answers(answers: &Answers) -> String {
+    [("Part 1", &answers.part1), ("Part 2", &answers.part2)]
+        .iter()
+        .filter_map(|(label, answer)| answer.as_ref().map(|answer| format_answer(label, answer)))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
 
-impl Run {
-    pub fn run(&self) -> Result<String> {
-        let code = self.day.get_code();
-        let part = &self.part;
-        let output = code.either(|code| match part{
-            Part::Part1 => code.part1(),
-            Part::Part2 => code.part2(),
-            Part::Both => code.both(),
-        }, |code| code.run());
-        Ok(output)
+fn format_answer(label: &str, answer: &Answer) -> String {
+    if answer.is_multiline() {
+        format!("{}:\n{}", label, answer)
+    } else {
+        format!("{}: {}", label, answer)
     }
 }
@@ -0,0 +1,107 @@
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::Result;
+use structopt::StructOpt;
+
+use aoc_2020::registry::{self, PartResults, Solution, BOTH_PARTS};
+use aoc_2020::{input, Answer, Part};
+
+use crate::table::{answer_cell, day_label, format_bytes, format_duration, Table};
+use crate::timing;
+
+#[derive(StructOpt)]
+pub struct RunAll {
+    /// Only run the solutions for this year
+    #[structopt(long, short)]
+    year: Option<u32>,
+    /// Time every day this many times instead, and show the mean of each step. This is where the
+    /// README's benchmark table comes from
+    #[structopt(long)]
+    repeat: Option<usize>,
+}
+
+impl RunAll {
+    pub fn run(&self) -> Result<String> {
+        let solutions = match self.year {
+            Some(year) => registry::for_year(year),
+            None => registry::all(),
+        };
+        if let Some(repeats) = self.repeat {
+            let (table, errors) = timing_table(&solutions, repeats);
+            let mut output = vec![table.render()];
+            output.extend(errors);
+            return Ok(output.join("\n\n"));
+        }
+        let mut table = Table::new(&["Day", "Name", "Part 1", "Part 2", "Time"]);
+        for solution in solutions {
+            // A broken day shouldn't stop the rest from running, it just gets its error in the table
+            let row = match run_solution(solution) {
+                Ok((results, time)) => vec![
+                    part_cell(&results.part1),
+                    part_cell(&results.part2),
+                    format_duration(time),
+                ],
+                Err(e) => vec![format!("error: {}", e), String::new(), String::new()],
+            };
+            let mut cells = vec![day_label(solution.year, solution.day), solution.name.to_string()];
+            cells.extend(row);
+            table.push(cells);
+        }
+        Ok(table.render())
+    }
+}
+
+/// Run both parts of a day against its usual input, timing from parse to answer
+pub fn run_solution(solution: &Solution) -> Result<(PartResults, Duration)> {
+    // Always use the input file, we can't share stdin between every day
+    let path = input::default_path(solution.year, solution.day);
+    let input = input::load(Some(&path), solution.year, solution.day)?;
+    let start = Instant::now();
+    let parsed = solution.solver.parse(&input)?;
+    let results = solution.run_parts(parsed.as_ref());
+    Ok((results, start.elapsed()))
+}
+
+/// The mean time of each step of every day. Errors would stretch the table, so they're handed back separately
+fn timing_table(solutions: &[&'static Solution], repeats: usize) -> (Table, Vec<String>) {
+    let mut table = Table::new(&["Day", "Parse", "Part 1", "Part 2", "Peak heap"]);
+    let mut errors = Vec::new();
+    for solution in solutions {
+        let path = input::default_path(solution.year, solution.day);
+        let timed = input::load(Some(&path), solution.year, solution.day)
+            .and_then(|input| timing::repeat(solution, &input, Part::Both, repeats));
+        let mut row = vec![day_label(solution.year, solution.day)];
+        match timed {
+            Ok((_, summaries)) => {
+                let mean = |label: &str| {
+                    summaries
+                        .iter()
+                        .find(|s| s.label == label)
+                        .map_or_else(|| "N/A".to_string(), |s| format_duration(s.mean))
+                };
+                row.push(mean("Parse"));
+                if summaries.iter().any(|s| s.label == BOTH_PARTS) {
+                    row.push(mean(BOTH_PARTS));
+                    row.push("with part 1".to_string());
+                } else {
+                    row.push(mean("Part 1"));
+                    row.push(mean("Part 2"));
+                }
+                row.push(format_bytes(summaries.iter().map(|s| s.peak_heap).max().unwrap_or(0)));
+            }
+            Err(e) => {
+                row.extend(vec!["error".to_string(); 4]);
+                errors.push(format!("{}: {}", day_label(solution.year, solution.day), e));
+            }
+        }
+        table.push(row);
+    }
+    (table, errors)
+}
+
+fn part_cell(result: &Result<Option<Answer>>) -> String {
+    match result {
+        Ok(answer) => answer_cell(answer),
+        Err(e) => format!("error: {}", e),
+    }
+}
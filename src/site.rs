@@ -0,0 +1,426 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use regex::Regex;
+
+use crate::{input, Part};
+
+/// Where the puzzles live. Can be pointed at a stand-in with $AOC_URL
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const URL_VAR: &str = "AOC_URL";
+
+/// The session cookie from a logged in browser, read from here before trying the config file
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// The site asks automated tools to say who they are
+const USER_AGENT: &str = concat!("aoc-2020/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+	pub status: u16,
+	pub body: String,
+}
+
+/// How requests get to the site. The real one goes over HTTP, tests swap in their own
+pub trait Transport {
+	fn get(&self, url: &str, session: &str) -> Result<Response>;
+	/// Send a form, the way the answer box on a puzzle page does
+	fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> Result<Response>;
+}
+
+pub struct HttpTransport;
+
+impl HttpTransport {
+	fn request(method: &str, url: &str, session: &str) -> ureq::Request {
+		ureq::request(method, url)
+			.set("Cookie", &format!("session={}", session))
+			.set("User-Agent", USER_AGENT)
+	}
+
+	fn response(url: &str, result: Result<ureq::Response, ureq::Error>) -> Result<Response> {
+		// ureq treats 4xx and 5xx as errors, but they're answers as far as we're concerned
+		let response = match result {
+			Ok(response) => response,
+			Err(ureq::Error::Status(_, response)) => response,
+			Err(e) => return Err(e).wrap_err_with(|| format!("Couldn't reach {}", url)),
+		};
+		let status = response.status();
+		let body = response.into_string().wrap_err_with(|| format!("Couldn't read the response from {}", url))?;
+		Ok(Response { status, body })
+	}
+}
+
+impl Transport for HttpTransport {
+	fn get(&self, url: &str, session: &str) -> Result<Response> {
+		HttpTransport::response(url, HttpTransport::request("GET", url, session).call())
+	}
+
+	fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> Result<Response> {
+		HttpTransport::response(url, HttpTransport::request("POST", url, session).send_form(form))
+	}
+}
+
+/// `~/.config/aoc/session`, the other place the session token can come from
+pub fn session_file() -> Option<PathBuf> {
+	let config = env::var_os("XDG_CONFIG_HOME")
+		.map(PathBuf::from)
+		.or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+	Some(config.join("aoc").join("session"))
+}
+
+/// The session token from $AOC_SESSION or the session file, if either has one
+pub fn session_token() -> Result<Option<String>> {
+	if let Ok(token) = env::var(SESSION_VAR) {
+		if !token.trim().is_empty() {
+			return Ok(Some(token.trim().to_string()));
+		}
+	}
+	match session_file() {
+		Some(path) if path.is_file() => {
+			let token = fs::read_to_string(&path).wrap_err_with(|| format!("Couldn't read {}", path.display()))?;
+			let token = token.trim();
+			Ok(if token.is_empty() { None } else { Some(token.to_string()) })
+		}
+		_ => Ok(None),
+	}
+}
+
+/// Talks to the site as a logged in user. Downloaded inputs are kept in the same place `input`
+/// looks for them, and anything already downloaded is never asked for again
+pub struct Site<T: Transport = HttpTransport> {
+	transport: T,
+	base_url: String,
+	session: String,
+	dir: PathBuf,
+}
+
+impl Site<HttpTransport> {
+	/// The real site (or $AOC_URL), logged in with whichever session token is set up
+	pub fn from_env() -> Result<Site> {
+		let session = session_token()?.ok_or_else(|| {
+			eyre!(
+				"No session token, set ${} or put it in {}",
+				SESSION_VAR,
+				session_file().map_or_else(|| "~/.config/aoc/session".to_string(), |p| p.display().to_string())
+			)
+		})?;
+		let base_url = env::var(URL_VAR).unwrap_or_else(|_| BASE_URL.to_string());
+		Ok(Site::new(HttpTransport, &base_url, &session))
+	}
+}
+
+impl<T: Transport> Site<T> {
+	pub fn new(transport: T, base_url: &str, session: &str) -> Site<T> {
+		Site {
+			transport,
+			base_url: base_url.trim_end_matches('/').to_string(),
+			session: session.to_string(),
+			dir: PathBuf::from(input::INPUT_DIR),
+		}
+	}
+
+	/// Keep the downloads somewhere other than `inputs`
+	pub fn with_dir(mut self, dir: &Path) -> Site<T> {
+		self.dir = dir.to_path_buf();
+		self
+	}
+
+	pub fn cached_path(&self, year: u32, day: u32) -> PathBuf {
+		input::path_in(&self.dir, year, day)
+	}
+
+	/// Make sure the input for a day is on disk, downloading it if it isn't, and say where it is
+	pub fn fetch(&self, year: u32, day: u32) -> Result<PathBuf> {
+		let path = self.cached_path(year, day);
+		// `new` leaves an empty file behind when it can't download, that doesn't count
+		if input::is_non_empty_file(&path) {
+			return Ok(path);
+		}
+		let url = format!("{}/{}/day/{}/input", self.base_url, year, day);
+		let response = self.transport.get(&url, &self.session)?;
+		match response.status {
+			200 if !response.body.is_empty() => {}
+			200 => return Err(eyre!("{} came back empty", url)),
+			404 => return Err(eyre!("The input for {} day {} isn't available yet", year, day)),
+			// The site answers a bad or expired cookie with a 400 (or a 500) asking you to log in
+			400 | 401 | 500 if response.body.contains("log in") => {
+				return Err(eyre!("The session token wasn't accepted, it has probably expired"));
+			}
+			status => {
+				let first_line = response.body.lines().next().unwrap_or_default();
+				return Err(eyre!("{} answered {}: {}", url, status, first_line));
+			}
+		}
+		if let Some(parent) = path.parent() {
+			fs::create_dir_all(parent).wrap_err_with(|| format!("Couldn't create {}", parent.display()))?;
+		}
+		// Write it somewhere else first, so an interrupted write doesn't look like a cached input
+		let partial = path.with_extension("part");
+		fs::write(&partial, &response.body).wrap_err_with(|| format!("Couldn't write {}", partial.display()))?;
+		fs::rename(&partial, &path).wrap_err_with(|| format!("Couldn't write {}", path.display()))?;
+		Ok(path)
+	}
+
+	/// Send an answer for one part of a day and say what the site made of it
+	pub fn submit(&self, year: u32, day: u32, part: Part, answer: &str) -> Result<Outcome> {
+		let level = match part {
+			Part::Part1 => "1",
+			Part::Part2 => "2",
+			Part::Both => return Err(eyre!("Answers are submitted one part at a time")),
+		};
+		let url = format!("{}/{}/day/{}/answer", self.base_url, year, day);
+		let response = self.transport.post(&url, &self.session, &[("level", level), ("answer", answer)])?;
+		match response.status {
+			200 => Ok(Outcome::parse(&response.body)),
+			400 | 401 | 500 if response.body.contains("log in") => {
+				Err(eyre!("The session token wasn't accepted, it has probably expired"))
+			}
+			status => {
+				let first_line = response.body.lines().next().unwrap_or_default();
+				Err(eyre!("{} answered {}: {}", url, status, first_line))
+			}
+		}
+	}
+}
+
+/// What the site said about a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+	Correct,
+	TooHigh,
+	TooLow,
+	/// Wrong, but the site didn't say which way
+	Wrong,
+	/// Answered too recently, the answer wasn't looked at. Holds how long is left, e.g. `4m 12s`
+	Wait(String),
+	/// That part has already been solved
+	AlreadySolved,
+	/// Something we don't recognise, the text of the page is kept so it can be shown
+	Unknown(String),
+}
+
+impl Outcome {
+	/// Read the outcome out of the page that comes back after submitting
+	pub fn parse(page: &str) -> Outcome {
+		let text = article_text(page);
+		if text.contains("That's the right answer") {
+			Outcome::Correct
+		} else if text.contains("your answer is too high") {
+			Outcome::TooHigh
+		} else if text.contains("your answer is too low") {
+			Outcome::TooLow
+		} else if text.contains("That's not the right answer") {
+			Outcome::Wrong
+		} else if text.contains("You gave an answer too recently") {
+			let left = Regex::new(r"You have (.+?) left to wait").unwrap();
+			let left = left.captures(&text).map_or_else(|| "a little while".to_string(), |c| c[1].to_string());
+			Outcome::Wait(left)
+		} else if text.contains("Did you already complete it") {
+			Outcome::AlreadySolved
+		} else {
+			Outcome::Unknown(text)
+		}
+	}
+
+	/// Whether the site looked at the answer and turned it down
+	pub fn is_wrong(&self) -> bool {
+		matches!(self, Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong)
+	}
+
+	/// How long the site wants left before the next answer, if it said to wait. Anything it says that
+	/// isn't hours, minutes and seconds is taken as a minute
+	pub fn wait(&self) -> Option<Duration> {
+		let left = match self {
+			Outcome::Wait(left) => left,
+			_ => return None,
+		};
+		let units = Regex::new(r"(\d+)\s*([hms])").unwrap();
+		let seconds: u64 = units
+			.captures_iter(left)
+			.map(|c| {
+				let amount: u64 = c[1].parse().unwrap_or(0);
+				match &c[2] {
+					"h" => amount * 60 * 60,
+					"m" => amount * 60,
+					_ => amount,
+				}
+			})
+			.sum();
+		Some(Duration::from_secs(if units.is_match(left) { seconds } else { 60 }))
+	}
+}
+
+impl fmt::Display for Outcome {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Outcome::Correct => write!(f, "correct"),
+			Outcome::TooHigh => write!(f, "too high"),
+			Outcome::TooLow => write!(f, "too low"),
+			Outcome::Wrong => write!(f, "wrong"),
+			Outcome::Wait(left) => write!(f, "answered too recently, {} left to wait", left),
+			Outcome::AlreadySolved => write!(f, "already solved"),
+			Outcome::Unknown(text) => write!(f, "unrecognised response: {}", text),
+		}
+	}
+}
+
+/// The message is in the page's `<article>`, without the markup around it
+fn article_text(page: &str) -> String {
+	let start = page.find("<article").unwrap_or(0);
+	let end = page[start..].find("</article>").map_or(page.len(), |end| start + end);
+	let tags = Regex::new(r"<[^>]*>").unwrap();
+	let text = tags.replace_all(&page[start..end], "");
+	text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::cell::RefCell;
+	use std::thread;
+
+	/// A fresh directory for a test to download into
+	fn scratch_dir(name: &str) -> PathBuf {
+		let dir = env::temp_dir().join(format!("aoc-site-{}-{}", name, std::process::id()));
+		let _ = fs::remove_dir_all(&dir);
+		dir
+	}
+
+	struct FakeTransport {
+		response: Response,
+		requests: RefCell<Vec<(String, String)>>,
+		forms: RefCell<Vec<Vec<(String, String)>>>,
+	}
+
+	impl FakeTransport {
+		fn answering(status: u16, body: &str) -> FakeTransport {
+			FakeTransport {
+				response: Response { status, body: body.to_string() },
+				requests: RefCell::new(Vec::new()),
+				forms: RefCell::new(Vec::new()),
+			}
+		}
+	}
+
+	impl Transport for &FakeTransport {
+		fn get(&self, url: &str, session: &str) -> Result<Response> {
+			self.requests.borrow_mut().push((url.to_string(), session.to_string()));
+			Ok(self.response.clone())
+		}
+
+		fn post(&self, url: &str, session: &str, form: &[(&str, &str)]) -> Result<Response> {
+			self.forms.borrow_mut().push(form.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect());
+			self.get(url, session)
+		}
+	}
+
+	/// Cut down versions of what the site sends back
+	fn page(message: &str) -> String {
+		format!("<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>", message)
+	}
+
+	#[test]
+	fn test_outcome_parse() {
+		let cases = [
+			("That's the right answer!  You are <em>one gold star</em> closer to saving Christmas.", Outcome::Correct),
+			("That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the full input data; please wait one minute before trying again. [<a href=\"/2019/day/1\">Return to Day 1</a>]", Outcome::TooHigh),
+			("That's not the right answer; your answer is too low.", Outcome::TooLow),
+			("That's not the right answer.  If you're stuck, make sure you're using the full input data.", Outcome::Wrong),
+			("You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 4m 12s left to wait.", Outcome::Wait("4m 12s".to_string())),
+			("You don't seem to be solving the right level.  Did you already complete it?", Outcome::AlreadySolved),
+		];
+		for (message, outcome) in cases.iter() {
+			assert_eq!(Outcome::parse(&page(message)), *outcome, "{}", message);
+		}
+		assert!(matches!(Outcome::parse(&page("Something new")), Outcome::Unknown(text) if text == "Something new"));
+	}
+
+	#[test]
+	fn test_submit() {
+		let transport = FakeTransport::answering(200, &page("That's not the right answer; your answer is too low."));
+		let site = Site::new(&transport, "http://example.com", "abc");
+		assert_eq!(site.submit(2019, 13, Part::Part2, "42").unwrap(), Outcome::TooLow);
+		assert_eq!(transport.requests.borrow()[0].0, "http://example.com/2019/day/13/answer");
+		assert_eq!(
+			transport.forms.borrow()[0],
+			vec![("level".to_string(), "2".to_string()), ("answer".to_string(), "42".to_string())]
+		);
+		assert!(site.submit(2019, 13, Part::Both, "42").is_err());
+	}
+
+	#[test]
+	fn test_fetch_caches() {
+		let dir = scratch_dir("cache");
+		let transport = FakeTransport::answering(200, "1\n2\n3\n");
+		let site = Site::new(&transport, "http://example.com/", "abc").with_dir(&dir);
+		let path = site.fetch(2019, 1).unwrap();
+		assert_eq!(path, dir.join("2019").join("Day1.txt"));
+		assert_eq!(fs::read_to_string(&path).unwrap(), "1\n2\n3\n");
+		site.fetch(2019, 1).unwrap();
+		assert_eq!(
+			*transport.requests.borrow(),
+			vec![("http://example.com/2019/day/1/input".to_string(), "abc".to_string())]
+		);
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_fetch_errors() {
+		let dir = scratch_dir("errors");
+		for (status, body) in &[(404, "Not Found"), (400, "Puzzle inputs differ by user.  Please log in to get your puzzle input."), (200, "")] {
+			let transport = FakeTransport::answering(*status, body);
+			let site = Site::new(&transport, "http://example.com", "abc").with_dir(&dir);
+			assert!(site.fetch(2019, 1).is_err(), "{} {:?}", status, body);
+		}
+		// Nothing half-written gets left behind to look like a cached input
+		assert!(!dir.join("2019").join("Day1.txt").exists());
+		let _ = fs::remove_dir_all(&dir);
+	}
+
+	#[test]
+	fn test_http_transport() {
+		let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+		let address = server.server_addr().to_ip().unwrap();
+		let handle = thread::spawn(move || {
+			let request = server.recv().unwrap();
+			let cookie = request
+				.headers()
+				.iter()
+				.find(|h| h.field.equiv("Cookie"))
+				.map(|h| h.value.to_string());
+			let url = request.url().to_string();
+			request.respond(tiny_http::Response::from_string("12\n34\n")).unwrap();
+			(url, cookie)
+		});
+		let dir = scratch_dir("http");
+		let site = Site::new(HttpTransport, &format!("http://{}", address), "secret").with_dir(&dir);
+		let path = site.fetch(2020, 22).unwrap();
+		assert_eq!(fs::read_to_string(path).unwrap(), "12\n34\n");
+		assert_eq!(handle.join().unwrap(), ("/2020/day/22/input".to_string(), Some("session=secret".to_string())));
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn test_http_submit() {
+		let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+		let address = server.server_addr().to_ip().unwrap();
+		let handle = thread::spawn(move || {
+			let mut request = server.recv().unwrap();
+			let mut form = String::new();
+			request.as_reader().read_to_string(&mut form).unwrap();
+			let summary = (request.method().to_string(), request.url().to_string(), form);
+			request.respond(tiny_http::Response::from_string(page("That's the right answer!"))).unwrap();
+			summary
+		});
+		let site = Site::new(HttpTransport, &format!("http://{}", address), "secret");
+		assert_eq!(site.submit(2019, 4, Part::Part1, "1686").unwrap(), Outcome::Correct);
+		assert_eq!(
+			handle.join().unwrap(),
+			("POST".to_string(), "/2019/day/4/answer".to_string(), "level=1&answer=1686".to_string())
+		);
+	}
+}
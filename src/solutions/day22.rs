@@ -1,37 +1,43 @@
-use crate::AoCDay;
+use color_eyre::eyre::Result;
+
+use crate::{Answer, AoCDay, Parts};
+use crate::registry::Solution;
 use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 
 pub struct Code;
 
-type Deck = VecDeque<u8>;
+pub const SOLUTION: Solution = Solution::new(2020, 22, "Crab Combat", &Parts(Code));
 
-const FL_CONT: &str = include_str!("../../inputs/Day22");
+type Deck = VecDeque<u8>;
 
 impl AoCDay for Code {
-    fn part1(&self) -> String {
-        let file_content: String = FL_CONT.to_string();
+    type Input = (Deck, Deck);
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
         //Use helper functions (see below) - these make it easier to unit test (see the bottom of the page!)
-        let (mut p1, mut p2) = parse_file_to_vecs(file_content);
+        Ok(parse_file_to_vecs(input.to_string()))
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        let (mut p1, mut p2) = input.clone();
         let winner = play_all_nonrec_rounds(&mut p1, &mut p2);
         //OK, we don't know or care who the winner is? Just need the final score
-        return match winner {
-            Winner::P1 => score_deck(p1).to_string(),
-            Winner::P2 => score_deck(p2).to_string(),
-        };
+        return Ok(match winner {
+            Winner::P1 => score_deck(p1).into(),
+            Winner::P2 => score_deck(p2).into(),
+        });
     }
 
-    fn part2(&self) -> String {
-        let file_content: String = FL_CONT.to_string();
-        //Use helper functions (see below) - these make it easier to unit test (see the bottom of the page!)
-        let (mut p1, mut p2) = parse_file_to_vecs(file_content);
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        let (mut p1, mut p2) = input.clone();
         let winner = play_all_rec_rounds(&mut p1, &mut p2);
         //Again, don't care who, just that
-        return match winner {
-            Winner::P1 => score_deck(p1).to_string(),
-            Winner::P2 => score_deck(p2).to_string(),
-        };
+        return Ok(match winner {
+            Winner::P1 => score_deck(p1).into(),
+            Winner::P2 => score_deck(p2).into(),
+        });
     }
 }
 
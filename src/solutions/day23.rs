@@ -1,45 +1,71 @@
-use crate::AoCDay;
+use color_eyre::eyre::{eyre, Result};
+
+use crate::{Answer, AoCDay, Parts};
+use crate::registry::Solution;
 
 type Cups = Vec<u32>;
 
 pub struct Code;
 
+pub const SOLUTION: Solution = Solution::new(2020, 23, "Crab Cups", &Parts(Code));
+
 impl AoCDay for Code {
 
-    fn part1(&self) -> String {
-        let mut cups = init(9);
-        run_moves(&mut cups, 100, 0);
-        return collect_cups_after_1(cups);
+    type Input = Cups;
+
+    /**
+     * The input is just the cup labels in order, they get stored zero indexed (so cup 1 is 0)
+     */
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        let labels = input
+            .trim()
+            .chars()
+            .map(|c| match c.to_digit(10) {
+                Some(d) if d > 0 => Ok(d - 1),
+                _ => Err(eyre!("{:?} is not a cup label", c)),
+            })
+            .collect::<Result<Cups>>()?;
+        if labels.is_empty() {
+            return Err(eyre!("There are no cups"));
+        }
+        return Ok(labels);
     }
 
-    fn part2(&self) -> String {
-        let mut cups = init(1000000);
-        run_moves(&mut cups, 10000000, 0);
-        return get_two_after_1(cups);
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
+        let (mut cups, first_cup) = init(input, 9);
+        run_moves(&mut cups, 100, first_cup);
+        return Ok(collect_cups_after_1(cups).into());
+    }
+
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
+        let (mut cups, first_cup) = init(input, 1000000);
+        run_moves(&mut cups, 10000000, first_cup);
+        return Ok(get_two_after_1(cups).into());
     }
 }
 
-fn init(array_len: u32) -> Cups{
+/**
+ * Build the linked list of cups from the labels in the input
+ * Any cups past the labelled ones just follow on in order, and the last cup loops back to the first
+ */
+fn init(labels: &Cups, array_len: u32) -> (Cups, u32){
     let mut arr: Cups = (0..array_len).collect();
     for i in 1..arr.len() {
         arr[i - 1] += 1;
     }
     arr.shrink_to_fit();
-    arr[0] = 5;
-    arr[5] = 6;
-    arr[6] = 1;
-    arr[1] = 3;
-    arr[3] = 7;
-    arr[7] = 2;
-    arr[2] = 4;
-    arr[4] = 8;
-    if array_len == 9 {
-        arr[8] = 0;
+    for pair in labels.windows(2) {
+        arr[pair[0] as usize] = pair[1];
+    }
+    let first_cup = labels[0];
+    let last_labelled = labels[labels.len() - 1] as usize;
+    if array_len as usize == labels.len() {
+        arr[last_labelled] = first_cup;
     }else{
-        arr[8] = 9;
-        arr[(array_len as usize) - 1] = 0;
+        arr[last_labelled] = labels.len() as u32;
+        arr[(array_len as usize) - 1] = first_cup;
     }
-    return arr;
+    return (arr, first_cup);
 }
 
 fn perform_move(cups: &mut Cups, current_cup: u32) -> u32{
@@ -90,11 +116,11 @@ fn collect_cups_after_1(cups: Cups) -> String{
     return cups_list;
 }
 
-fn get_two_after_1(cups: Cups) -> String{
+fn get_two_after_1(cups: Cups) -> usize{
     let first_num = cups[0];
     let second_num = cups[first_num as usize];
     let final_mult: usize = ((first_num + 1) as usize) * ((second_num + 1) as usize);
-    return final_mult.to_string();
+    return final_mult;
 }
 
 #[cfg(test)]
@@ -197,6 +223,6 @@ mod tests_all{
         run_moves(&mut arr, 10000000, curr_cup);
         assert_eq!(arr[0], (934001 -1));
         assert_eq!(arr[934001 -1], (159792 -1));
-        assert_eq!(get_two_after_1(arr),"149245887792");
+        assert_eq!(get_two_after_1(arr),149245887792);
     }
 }
\ No newline at end of file
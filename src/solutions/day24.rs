@@ -1,9 +1,14 @@
-use crate::AoCDay;
+use color_eyre::eyre::Result;
+
+use crate::{Answer, AoCDay, Parts};
+use crate::registry::Solution;
 use std::collections::HashMap;
 use std::ops::Add;
 
 pub struct Code;
 
+pub const SOLUTION: Solution = Solution::new(2020, 24, "Lobby Layout", &Parts(Code));
+
 type NUM = isize;
 /**
  * Use boolean as indicator true = black, false/undef = white
@@ -37,20 +42,22 @@ enum DIRECTION {
 
 type ACTIONS = Vec<Vec<DIRECTION>>;
 
-const FL_CONT: &str = include_str!("../../inputs/Day24");
-
 impl AoCDay for Code {    
-    fn part1(&self) -> String {
-        let file_conts: String = FL_CONT.to_string();
+    type Input = String;
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, input: &Self::Input) -> Result<Answer> {
         let mut grid: XYZGrid = HashMap::new();
-        flip_tiles(tokenize(file_conts.to_string()), &mut grid);
-        return count_black_tiles(&grid).to_string();
+        flip_tiles(tokenize(input.to_string()), &mut grid);
+        return Ok(count_black_tiles(&grid).into());
     }
-    fn part2(&self) -> String {
-        let file_conts: String = FL_CONT.to_string();
+    fn part2(&self, input: &Self::Input) -> Result<Answer> {
         let mut grid: XYZGrid = HashMap::new();
-        flip_tiles(tokenize(file_conts.to_string()), &mut grid);
-        return ConwayFloor::from_grid(&grid).ticks(100).to_string();
+        flip_tiles(tokenize(input.to_string()), &mut grid);
+        return Ok(ConwayFloor::from_grid(&grid).ticks(100).into());
     }
 }
 
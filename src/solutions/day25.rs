@@ -1,18 +1,37 @@
 use std::usize;
 
-use crate::{SinglePart};
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use crate::{Answer, Single, SinglePart};
+use crate::registry::Solution;
 pub struct Code;
 
+pub const SOLUTION: Solution = Solution::new(2020, 25, "Combo Breaker", &Single(Code));
+
 type Loops = usize;
 type Encryption = usize;
 const SUBJECT: usize = 7;
 const MODULAR: usize = 20201227;
-const CARD_PUBLIC_KEY: usize = 9717666;
-const DOOR_PUBLIC_KEY: usize = 20089533;
 
 impl SinglePart for Code {
-    fn run(&self) -> String {
-        return solve(CARD_PUBLIC_KEY, DOOR_PUBLIC_KEY).0.to_string();
+    /**
+     * The input is the card's public key then the door's, one per line
+     */
+    type Input = (Encryption, Encryption);
+
+    fn parse(&self, input: &str) -> Result<Self::Input> {
+        let mut keys = input
+            .lines()
+            .map(|l| l.trim().parse::<Encryption>().wrap_err_with(|| format!("{:?} is not a public key", l)));
+        let card_public_key = keys.next().ok_or_else(|| eyre!("Missing the card's public key"))??;
+        let door_public_key = keys.next().ok_or_else(|| eyre!("Missing the door's public key"))??;
+        return Ok((card_public_key, door_public_key));
+    }
+
+    fn run(&self, input: &Self::Input) -> Result<(Answer, Option<Answer>)> {
+        let (card_public_key, door_public_key) = *input;
+        //Day 25 only ever has the one puzzle
+        return Ok((solve(card_public_key, door_public_key).0.into(), None));
     }
 }
 
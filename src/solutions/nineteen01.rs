@@ -1,35 +1,43 @@
-use crate::AoCDay;
+use color_eyre::eyre::{Result, WrapErr};
+
+use crate::{Answer, AoCDay, Parts};
+use crate::registry::Solution;
 
 pub struct Code;
 
-const FL_CONT: &str = include_str!("../../inputs/2019/Day1.txt");
+pub const SOLUTION: Solution = Solution::new(2019, 1, "The Tyranny of the Rocket Equation", &Parts(Code));
 
 impl AoCDay for Code{
-	fn part1(&self) -> String {
-        let file_content: String = FL_CONT.to_string();
-		let initial_fuel_requirements: usize = file_content
+	type Input = Vec<usize>;
+
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		input
 			//Automatically split by line
 			.lines()
-			//Parse each line into a number and calc fuel
-			.map(|lc| calculate_fuel(lc.parse().unwrap()))
+			//Parse each line into a number
+			.map(|lc| lc.trim().parse().wrap_err_with(|| format!("{:?} is not a module mass", lc)))
+			.collect()
+	}
+
+	fn part1(&self, input: &Self::Input) -> Result<Answer> {
+		let initial_fuel_requirements: usize = input
+			.iter()
+			//Calc fuel for each
+			.map(|&module| calculate_fuel(module))
 			// Fold them all as a sum
 			.fold(0,|a, b| a + b);
-		return initial_fuel_requirements.to_string();
+		return Ok(initial_fuel_requirements.into());
     }
 
-	fn part2(&self) -> String {
-		let file_content: String = FL_CONT.to_string();
-		let fuel_requirements: usize = file_content
-			//Automatically split by line
-			.lines()
-			//Parse each line into a number
-			.map(|lc| lc.parse().unwrap())
+	fn part2(&self, input: &Self::Input) -> Result<Answer> {
+		let fuel_requirements: usize = input
+			.iter()
 			//Map each to their fuel requirements
-			.map(|module| calculate_recursive_fuel(module))
+			.map(|&module| calculate_recursive_fuel(module))
 			//Now map the recursive need
 			// Fold them all as a sum
 			.fold(0,|a, b| a + b);
-		return fuel_requirements.to_string();
+		return Ok(fuel_requirements.into());
 	}
 }
 
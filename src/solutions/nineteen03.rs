@@ -1,17 +1,24 @@
 use std::collections::{HashMap};
 
-use crate::{SinglePart};
+use color_eyre::eyre::Result;
+
+use crate::{Answer, Single, SinglePart};
+use crate::registry::Solution;
 
-const FL_CONT: &str = include_str!("../../inputs/2019/Day3.txt");
 pub struct Code;
 
+pub const SOLUTION: Solution = Solution::new(2019, 3, "Crossed Wires", &Single(Code));
+
 impl SinglePart for Code{
-	fn run(&self) -> String {
-        let file_content: String = FL_CONT.to_string();
-		let res = action_str(file_content);
-        //return "Part 1\t" + res[0].to_string() + "\nPart 2\t" + res[1].to_string();
-		let fmt = format!("Part 1:\t{}\nPart 2:\t{}\n",res.0, res.1);
-		return fmt;
+	type Input = String;
+
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		Ok(input.to_string())
+	}
+
+	fn run(&self, input: &Self::Input) -> Result<(Answer, Option<Answer>)> {
+		let res = action_str(input.clone());
+		return Ok((res.0.into(), Some(res.1.into())));
     }
 }
 
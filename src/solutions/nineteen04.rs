@@ -1,14 +1,34 @@
-use crate::SinglePart;
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use crate::{Answer, Single, SinglePart};
+use crate::registry::Solution;
 
 pub struct Code;
-pub const MIN: u32 = 168630;
-pub const MAX: u32 = 718098;
+
+pub const SOLUTION: Solution = Solution::new(2019, 4, "Secure Container", &Single(Code));
+
+/// The input is just the range, written as `min-max`
+pub fn parse_range(input: &str) -> Result<(u32, u32)> {
+	let (min, max) = input
+		.trim()
+		.split_once('-')
+		.ok_or_else(|| eyre!("Range should look like min-max"))?;
+	Ok((
+		min.parse().wrap_err("Range minimum should be a number")?,
+		max.parse().wrap_err("Range maximum should be a number")?,
+	))
+}
 
 impl SinglePart for Code{
-	fn run(&self) -> String {
-		let res = count_passwords_between(MIN, MAX);
-		let fmt = format!("Part 1:\t{}\nPart 2:\t{}\n",res.0, res.1);
-		return fmt;
+	type Input = (u32, u32);
+
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		parse_range(input)
+	}
+
+	fn run(&self, input: &Self::Input) -> Result<(Answer, Option<Answer>)> {
+		let res = count_passwords_between(input.0, input.1);
+		return Ok((res.0.into(), Some(res.1.into())));
     }
 }
 
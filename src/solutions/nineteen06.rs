@@ -1,10 +1,13 @@
 use std::{collections::HashMap, str};
 
-use crate::SinglePart;
+use color_eyre::eyre::Result;
+
+use crate::{Answer, Single, SinglePart};
+use crate::registry::Solution;
 
 pub struct Code;
 
-pub const FL_CONT: &str = include_str!("../../inputs/2019/Day6.txt");
+pub const SOLUTION: Solution = Solution::new(2019, 6, "Universal Orbit Map", &Single(Code));
 
 enum SpaceThing<'a>{
 	COM,
@@ -35,11 +38,16 @@ struct Orbit<'a> {
 type OGraph<'a> = HashMap<&'a str, SpaceThing<'a>>;
 
 impl SinglePart for Code{
-	fn run(&self) -> String {
-		let graph: OGraph = make_hashmap(parse_input(FL_CONT));
+	type Input = String;
+
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		Ok(input.to_string())
+	}
+
+	fn run(&self, input: &Self::Input) -> Result<(Answer, Option<Answer>)> {
+		let graph: OGraph = make_hashmap(parse_input(input));
 		let res: (u32, u32) = (count_orbits(&graph),count_path(&graph, "YOU", "SAN"));
-		let fmt = format!("Part 1:\t{}\nPart 2:\t{}\n",res.0, res.1);
-		return fmt;
+		return Ok((res.0.into(), Some(res.1.into())));
     }
 }
 
@@ -1,59 +1,69 @@
 use std::ops::RangeInclusive;
 
-use crate::AoCDay;
-use crate::intcode::Memory;
+use color_eyre::eyre::{eyre, Result};
+
+use crate::{Answer, AoCDay, Parts};
+use crate::registry::Solution;
+use crate::intcode::{memory_from_str, Memory};
+use crate::intcode::topology::Topology;
 use itertools::Itertools;
 use rayon::prelude::*;
 
-use super::super::intcode::IntCodeVM;
-use super::super::intcode::NumType;
-
 pub struct Code;
 
-pub const DAY_7_DATA:[NumType;519] = [3,8,1001,8,10,8,105,1,0,0,21,34,59,76,101,114,195,276,357,438,99999,3,9,1001,9,4,9,1002,9,4,9,4,9,99,3,9,102,4,9,9,101,2,9,9,102,4,9,9,1001,9,3,9,102,2,9,9,4,9,99,3,9,101,4,9,9,102,5,9,9,101,5,9,9,4,9,99,3,9,102,2,9,9,1001,9,4,9,102,4,9,9,1001,9,4,9,1002,9,3,9,4,9,99,3,9,101,2,9,9,1002,9,3,9,4,9,99,3,9,101,2,9,9,4,9,3,9,1002,9,2,9,4,9,3,9,1002,9,2,9,4,9,3,9,1001,9,1,9,4,9,3,9,102,2,9,9,4,9,3,9,101,1,9,9,4,9,3,9,1001,9,1,9,4,9,3,9,1002,9,2,9,4,9,3,9,1001,9,2,9,4,9,3,9,102,2,9,9,4,9,99,3,9,101,2,9,9,4,9,3,9,1002,9,2,9,4,9,3,9,1001,9,1,9,4,9,3,9,101,2,9,9,4,9,3,9,1002,9,2,9,4,9,3,9,102,2,9,9,4,9,3,9,101,1,9,9,4,9,3,9,1001,9,2,9,4,9,3,9,101,2,9,9,4,9,3,9,1001,9,1,9,4,9,99,3,9,102,2,9,9,4,9,3,9,1001,9,1,9,4,9,3,9,1001,9,1,9,4,9,3,9,102,2,9,9,4,9,3,9,1001,9,2,9,4,9,3,9,1002,9,2,9,4,9,3,9,102,2,9,9,4,9,3,9,102,2,9,9,4,9,3,9,101,1,9,9,4,9,3,9,101,2,9,9,4,9,99,3,9,1002,9,2,9,4,9,3,9,102,2,9,9,4,9,3,9,102,2,9,9,4,9,3,9,102,2,9,9,4,9,3,9,1002,9,2,9,4,9,3,9,1002,9,2,9,4,9,3,9,101,2,9,9,4,9,3,9,102,2,9,9,4,9,3,9,101,2,9,9,4,9,3,9,101,2,9,9,4,9,99,3,9,1002,9,2,9,4,9,3,9,1001,9,1,9,4,9,3,9,101,2,9,9,4,9,3,9,101,2,9,9,4,9,3,9,101,2,9,9,4,9,3,9,101,1,9,9,4,9,3,9,1002,9,2,9,4,9,3,9,1002,9,2,9,4,9,3,9,1001,9,1,9,4,9,3,9,101,2,9,9,4,9,99];
+pub const SOLUTION: Solution = Solution::new(2019, 7, "Amplification Circuit", &Parts(Code));
+
 
 impl AoCDay for Code{
-	fn part1(&self) -> String {
-		let memory = Vec::from(DAY_7_DATA);
-		return perform_work(memory, 0..=4, false).to_string();
-    }
-	fn part2(&self) -> String {
-		let memory = Vec::from(DAY_7_DATA);
-		return perform_work(memory, 5..=9, true).to_string();
+	type Input = Memory;
+
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		Ok(memory_from_str(input)?)
+	}
+
+	fn part1(&self, input: &Self::Input) -> Result<Answer> {
+		Ok(perform_work(input.clone(), 0..=4, false, true)?.into())
 	}
+	fn part2(&self, input: &Self::Input) -> Result<Answer> {
+		Ok(perform_work(input.clone(), 5..=9, true, true)?.into())
+	}
+}
+
+/// The biggest signal out of the last amplifier for any order of the phase settings, with `feedback` it loops back
+/// round to the first. `parallel` tries the orders on rayon's threads
+pub fn perform_work(memory: Memory, range: RangeInclusive<i64>, feedback: bool, parallel: bool) -> Result<i64>{
+	let signal = |phases: Vec<i64>| -> Result<i64> {
+		let inputs = phases.iter().enumerate().map(|(i, &phase)| if i == 0 { vec![phase, 0] } else { vec![phase] }).collect();
+		let report = Topology::chain(&memory, inputs, feedback).run()?;
+		let last = report.outputs.last().ok_or_else(|| eyre!("There are no amplifiers"))?;
+		last.last().copied().ok_or_else(|| eyre!("The last amplifier gave no output"))
+	};
+	let amplifiers = range.clone().count();
+	let orders = range.permutations(amplifiers);
+	let signals = if parallel {
+		orders.par_bridge().map(signal).collect::<Result<Vec<_>>>()?
+	} else {
+		orders.map(signal).collect::<Result<Vec<_>>>()?
+	};
+	signals.into_iter().max().ok_or_else(|| eyre!("There were no phase settings to try"))
 }
 
-pub fn perform_work(memory: Memory, range: RangeInclusive<i64>, link_a_and_e: bool) -> i64{
-	range
-		.permutations(5)
-		.par_bridge()
-		.map(|res|{
-			let mut vma = IntCodeVM::new(memory.clone());
-			let mut vmb = IntCodeVM::new_networked(memory.clone(), &vma);
-			let mut vmc = IntCodeVM::new_networked(memory.clone(), &vmb);
-			let mut vmd = IntCodeVM::new_networked(memory.clone(), &vmc);
-			let mut vme = IntCodeVM::new_networked(memory.clone(), &vmd);
-			if link_a_and_e {
-				vma.network_to(&vme);
-			}
-			vma.push_input(res[0]);
-			vma.push_input(0);
-			vmb.push_input(res[1]);
-			vmc.push_input(res[2]);
-			vmd.push_input(res[3]);
-			vme.push_input(res[4]);
-			loop{
-				vma.run_all();
-				vmb.run_all();
-				vmc.run_all();
-				vmd.run_all();
-				vme.run_all();
-				if vma.is_stopped() && vmb.is_stopped() && vmc.is_stopped() && vmd.is_stopped() && vme.is_stopped() {
-					break;
-				}
-			}
-			return vme.output().take().pop_front().unwrap();
-		})
-		.max_by(|x, y| x.cmp(y))
-		.unwrap_or(0)
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_examples() {
+		let chain = vec![3, 15, 3, 16, 1002, 16, 10, 16, 1, 16, 15, 15, 4, 15, 99, 0, 0];
+		let looped = vec![
+			3, 26, 1001, 26, -4, 26, 3, 27, 1002, 27, 2, 27, 1, 27, 26, 27, 4, 27, 1001, 28, -1, 28, 1005, 28, 6, 99, 0,
+			0, 5,
+		];
+		for &parallel in &[false, true] {
+			assert_eq!(perform_work(chain.clone(), 0..=4, false, parallel).unwrap(), 43210);
+			assert_eq!(perform_work(looped.clone(), 5..=9, true, parallel).unwrap(), 139629729);
+			// However many amplifiers there are
+			assert_eq!(perform_work(chain.clone(), 0..=2, false, parallel).unwrap(), 210);
+		}
+	}
+}
@@ -1,9 +1,12 @@
-use crate::AoCDay;
+use color_eyre::eyre::Result;
+
+use crate::{Answer, AoCDay, Parts};
+use crate::registry::Solution;
 use super::super::image_layer::*;
 
 pub struct Code;
 
-pub const DAY_8_DATA: &str = include_str!("../../inputs/2019/Day8.txt");
+pub const SOLUTION: Solution = Solution::new(2019, 8, "Space Image Format", &Parts(Code));
 
 const HEIGHT: usize = 6;
 const WIDTH: usize = 25;
@@ -78,25 +81,28 @@ impl Image {
 	}
 }
 
-pub fn part_1_impl(input: &str, width: usize, height: usize) -> String {
+pub fn part_1_impl(input: &str, width: usize, height: usize) -> usize {
 	return Image::read_in_str(input, width, height)
-		.worlds_worst_checksum()
-		.to_string();
+		.worlds_worst_checksum();
 }
 
-pub fn part_2_impl(input: &str, width: usize, height: usize) -> String {
+pub fn part_2_impl(input: &str, width: usize, height: usize) -> ImageLayer {
 	return Image::read_in_str(input, width, height)
-		.collapse_layers_to_one()
-		.to_string();
+		.collapse_layers_to_one();
 }
 
 impl AoCDay for Code {
-	fn part1(&self) -> String {
-		return part_1_impl(DAY_8_DATA, WIDTH, HEIGHT);
+	type Input = String;
+
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		Ok(input.trim().to_string())
+	}
+
+	fn part1(&self, input: &Self::Input) -> Result<Answer> {
+		return Ok(part_1_impl(input, WIDTH, HEIGHT).into());
 	}
-	fn part2(&self) -> String {
-		//let memory = Vec::from(DAY_7_DATA);
-		return part_2_impl(DAY_8_DATA, WIDTH, HEIGHT);
+	fn part2(&self, input: &Self::Input) -> Result<Answer> {
+		return Ok(part_2_impl(input, WIDTH, HEIGHT).into());
 	}
 }
 
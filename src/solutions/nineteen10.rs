@@ -1,8 +1,12 @@
-use crate::SinglePart;
+use color_eyre::eyre::Result;
+
+use crate::{Answer, Single, SinglePart};
+use crate::registry::Solution;
 use rayon::prelude::*;
 pub struct Code;
 
-pub const FL_CONT: &str = include_str!("../../inputs/2019/Day10.txt");
+pub const SOLUTION: Solution = Solution::new(2019, 10, "Monitoring Station", &Single(Code));
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 enum Quadrant {
 	N,
@@ -160,14 +164,10 @@ fn part_2_solver(map: Vec<Asteroid>, part_a_asteroid: &Asteroid) -> usize {
 				a.quad.partial_cmp(&b.quad).unwrap_or(a.relx.cmp(&b.relx))
 			}
 		});
-		rebased_asteroids.drain_filter(|eval| {
-			match currently_seeable
+		rebased_asteroids.retain(|eval| {
+			!currently_seeable
 				.iter()
-				.find(|obs| obs.ox == eval.ox && obs.oy == eval.oy)
-			{
-				Some(_x) => true,
-				None => false,
-			}
+				.any(|obs| obs.ox == eval.ox && obs.oy == eval.oy)
 		});
 		seeables.append(&mut currently_seeable);
 		if rebased_asteroids.len() == 0 {
@@ -178,23 +178,24 @@ fn part_2_solver(map: Vec<Asteroid>, part_a_asteroid: &Asteroid) -> usize {
 	return (num200.ox as usize * 100) + (num200.oy as usize);
 }
 
-pub fn solve(input: &str) -> String {
+pub fn solve(input: &str) -> (usize, usize) {
 	let map: Vec<Asteroid> = create_map(input);
 	let aster_mp = find_best_asteroid(map.clone());
 	let part_a_answer: usize = aster_mp.1.len();
 	//OK, so now we can routinely eliminate asteroids until we find the 200th!
-
-	let fmt = format!(
-		"Part 1:\t{}\nPart 2:\t{}\n",
-		part_a_answer,
-		part_2_solver(map, &aster_mp.0)
-	);
-	return fmt;
+	return (part_a_answer, part_2_solver(map, &aster_mp.0));
 }
 
 impl SinglePart for Code {
-	fn run(&self) -> String {
-		solve(FL_CONT)
+	type Input = String;
+
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		Ok(input.to_string())
+	}
+
+	fn run(&self, input: &Self::Input) -> Result<(Answer, Option<Answer>)> {
+		let (part1, part2) = solve(input);
+		Ok((part1.into(), Some(part2.into())))
 	}
 }
 #[cfg(test)]
@@ -1,5 +1,8 @@
 use std::cmp::Ordering;
-use crate::AoCDay;
+use regex::Regex;
+use color_eyre::eyre::{eyre, Result};
+use crate::{Answer, AoCDay, Parts};
+use crate::registry::Solution;
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 struct Axis {
@@ -162,13 +165,39 @@ pub fn part_2_impl(moon_a: (i64,i64,i64), moon_b: (i64,i64,i64), moon_c: (i64,i6
 	return jup.find_steps_till_repeat();
 }
 
+type MoonPosition = (i64, i64, i64);
+
+/// Pull the four `<x=.., y=.., z=..>` lines out of the input
+pub fn parse_moons(input: &str) -> Result<(MoonPosition, MoonPosition, MoonPosition, MoonPosition)> {
+	let moon_regx: Regex = Regex::new(r"<x=(-?\d+), y=(-?\d+), z=(-?\d+)>").unwrap();
+	let moons: Vec<MoonPosition> = moon_regx
+		.captures_iter(input)
+		.map(|cap| (cap[1].parse().unwrap(), cap[2].parse().unwrap(), cap[3].parse().unwrap()))
+		.collect();
+	if moons.len() != 4 {
+		return Err(eyre!("Jupiter should have exactly four moons, found {}", moons.len()));
+	}
+	Ok((moons[0], moons[1], moons[2], moons[3]))
+}
+
 pub struct Code;
+
+pub const SOLUTION: Solution = Solution::new(2019, 12, "The N-Body Problem", &Parts(Code));
+
 impl AoCDay for Code {
-	fn part1(&self) -> String {
-		return part_1_impl((-17,9,-5), (-1,7,13), (-19,12,5), (-6,-6,-4)).to_string();
+	type Input = (MoonPosition, MoonPosition, MoonPosition, MoonPosition);
+
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		parse_moons(input)
+	}
+
+	fn part1(&self, input: &Self::Input) -> Result<Answer> {
+		let &(a, b, c, d) = input;
+		return Ok(part_1_impl(a, b, c, d).into());
 	}
-	fn part2(&self) -> String {
-		return part_2_impl((-17,9,-5), (-1,7,13), (-19,12,5), (-6,-6,-4)).to_string();
+	fn part2(&self, input: &Self::Input) -> Result<Answer> {
+		let &(a, b, c, d) = input;
+		return Ok(part_2_impl(a, b, c, d).into());
 	}
 }
 
@@ -176,6 +205,12 @@ impl AoCDay for Code {
 mod tests {
 	use super::*;
 
+	#[test]
+	fn test_parse_moons(){
+		let moons = parse_moons("<x=-1, y=0, z=2>\n<x=2, y=-10, z=-7>\n<x=4, y=-8, z=8>\n<x=3, y=5, z=-1>\n").unwrap();
+		assert_eq!(moons, ((-1,0,2), (2,-10,-7), (4,-8,8), (3,5,-1)));
+	}
+
 	#[test]
 	fn test_a_part_1(){
 		let mut jup: Jupiter = Jupiter::new((-1,0,2), (2,-10,-7), (4,-8,8), (3,5,-1));
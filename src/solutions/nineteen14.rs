@@ -2,10 +2,15 @@ use std::collections::HashMap;
 
 use regex::Regex;
 
-use crate::AoCDay;
+use color_eyre::eyre::Result;
+
+use crate::{Answer, AoCDay, Parts};
+use crate::registry::Solution;
 
 pub struct Code;
 
+pub const SOLUTION: Solution = Solution::new(2019, 14, "Space Stoichiometry", &Parts(Code));
+
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 struct Ingredient {
 	nm: String,
@@ -141,13 +146,13 @@ impl NanoFactory {
 	}
 }
 
-pub fn part_1_impl(input: &str) -> String {
-	NanoFactory::from_reactions_list(input).make_desired_fuel(1).to_string()
+pub fn part_1_impl(input: &str) -> OreCost {
+	NanoFactory::from_reactions_list(input).make_desired_fuel(1)
 }
 
 // Work out what straight dividing it would be (worst case) fuel creation,
 // And then massively increase that (double? triple?) that as a goal so we can kinda binary search the possible values?
-fn target_ore_use(nfac: NanoFactory, target_ore_cost: OreCost) -> String {
+fn target_ore_use(nfac: NanoFactory, target_ore_cost: OreCost) -> OreCost {
 	let cost_one: OreCost = nfac.clone().make_desired_fuel(1);
 	//Worst case
 	let mut fuel_min_case: OreCost = target_ore_cost / cost_one;
@@ -160,7 +165,7 @@ fn target_ore_use(nfac: NanoFactory, target_ore_cost: OreCost) -> String {
 		let ore_cost = nfac.clone().make_desired_fuel(desired_fuel);
 		if ore_cost == target_ore_cost {
 			//Lol, just return this
-			return desired_fuel.to_string();
+			return desired_fuel;
 		} else if ore_cost < target_ore_cost {
 			fuel_min_case = desired_fuel;
 		} else {
@@ -169,23 +174,27 @@ fn target_ore_use(nfac: NanoFactory, target_ore_cost: OreCost) -> String {
 	}
 	//Fuel max and fuel min should be the same OR max should be one above the min
 	// min should be correct tho since it's always going to come under (looking at the above code)?
-	fuel_min_case.to_string()
+	fuel_min_case
 }
 
 
-pub fn part_2_impl(input: &str) -> String {
+pub fn part_2_impl(input: &str) -> OreCost {
 	target_ore_use(NanoFactory::from_reactions_list(input), 1_000_000_000_000)
 }
 
-pub const DAY_14_DATA: &str = include_str!("../../inputs/2019/Day14.txt");
-
 impl AoCDay for Code {
-	fn part1(&self) -> String {
-		return part_1_impl(DAY_14_DATA);
+	type Input = String;
+
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		Ok(input.to_string())
+	}
+
+	fn part1(&self, input: &Self::Input) -> Result<Answer> {
+		return Ok(part_1_impl(input).into());
 	}
 
-	fn part2(&self) -> String {
-		return part_2_impl(DAY_14_DATA);
+	fn part2(&self, input: &Self::Input) -> Result<Answer> {
+		return Ok(part_2_impl(input).into());
 	}
 }
 
@@ -201,29 +210,29 @@ mod tests {
 
 	#[test]
 	fn test_example_a() {
-		assert_eq!(part_1_impl(STR_A), "31");
+		assert_eq!(part_1_impl(STR_A), 31);
 	}
 
 	#[test]
 	fn test_example_b() {
-		assert_eq!(part_1_impl(STR_B), "165");
+		assert_eq!(part_1_impl(STR_B), 165);
 	}
 
 	#[test]
 	fn test_example_c() {
-		assert_eq!(part_1_impl(STR_C), "13312");
-		assert_eq!(part_2_impl(STR_C), "82892753");
+		assert_eq!(part_1_impl(STR_C), 13312);
+		assert_eq!(part_2_impl(STR_C), 82892753);
 	}
 
 	#[test]
 	fn test_example_d() {
-		assert_eq!(part_1_impl(STR_D), "180697");
-		assert_eq!(part_2_impl(STR_D), "5586022");
+		assert_eq!(part_1_impl(STR_D), 180697);
+		assert_eq!(part_2_impl(STR_D), 5586022);
 	}
 
 	#[test]
 	fn test_example_e() {
-		assert_eq!(part_1_impl(STR_E), "2210736");
-		assert_eq!(part_2_impl(STR_E), "460664");
+		assert_eq!(part_1_impl(STR_E), 2210736);
+		assert_eq!(part_2_impl(STR_E), 460664);
 	}
 }
\ No newline at end of file
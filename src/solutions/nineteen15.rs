@@ -1,314 +1,103 @@
-use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use color_eyre::eyre::{eyre, Result};
 
-use crate::coords::Coords;
+use crate::cell::CellType;
 use crate::direction::Direction;
-use crate::image_layer::{ImageLayer, Pixel};
-use crate::intcode::{IntCodeVM, NumType};
-use crate::SinglePart;
+use crate::image_layer::ImageLayer;
+use crate::intcode::{memory_from_str, IntCodeVM, Memory, NumType};
+use crate::robot::{Explorer, Protocol, Step};
+use crate::{Answer, Single, SinglePart};
+use crate::registry::Solution;
 
-const DAY_15_DATA: [NumType; 1045] = [3, 1033, 1008, 1033, 1, 1032, 1005, 1032, 31, 1008, 1033, 2, 1032, 1005, 1032, 58, 1008, 1033, 3, 1032, 1005, 1032, 81, 1008, 1033, 4, 1032, 1005, 1032, 104, 99, 101, 0, 1034, 1039, 1001, 1036, 0, 1041, 1001, 1035, -1, 1040, 1008, 1038, 0, 1043, 102, -1, 1043, 1032, 1, 1037, 1032, 1042, 1106, 0, 124, 101, 0, 1034, 1039, 101, 0, 1036, 1041, 1001, 1035, 1, 1040, 1008, 1038, 0, 1043, 1, 1037, 1038, 1042, 1105, 1, 124, 1001, 1034, -1, 1039, 1008, 1036, 0, 1041, 1002, 1035, 1, 1040, 1001, 1038, 0, 1043, 1002, 1037, 1, 1042, 1106, 0, 124, 1001, 1034, 1, 1039, 1008, 1036, 0, 1041, 102, 1, 1035, 1040, 1001, 1038, 0, 1043, 102, 1, 1037, 1042, 1006, 1039, 217, 1006, 1040, 217, 1008, 1039, 40, 1032, 1005, 1032, 217, 1008, 1040, 40, 1032, 1005, 1032, 217, 1008, 1039, 39, 1032, 1006, 1032, 165, 1008, 1040, 39, 1032, 1006, 1032, 165, 1101, 2, 0, 1044, 1106, 0, 224, 2, 1041, 1043, 1032, 1006, 1032, 179, 1102, 1, 1, 1044, 1106, 0, 224, 1, 1041, 1043, 1032, 1006, 1032, 217, 1, 1042, 1043, 1032, 1001, 1032, -1, 1032, 1002, 1032, 39, 1032, 1, 1032, 1039, 1032, 101, -1, 1032, 1032, 101, 252, 1032, 211, 1007, 0, 59, 1044, 1106, 0, 224, 1101, 0, 0, 1044, 1106, 0, 224, 1006, 1044, 247, 101, 0, 1039, 1034, 1001, 1040, 0, 1035, 1002, 1041, 1, 1036, 102, 1, 1043, 1038, 101, 0, 1042, 1037, 4, 1044, 1105, 1, 0, 33, 20, 19, 43, 28, 91, 62, 55, 96, 28, 52, 9, 24, 99, 11, 45, 80, 58, 96, 2, 8, 76, 1, 37, 5, 95, 18, 6, 97, 67, 47, 4, 19, 29, 74, 57, 45, 65, 17, 43, 93, 33, 71, 93, 26, 2, 86, 11, 31, 74, 85, 36, 94, 20, 89, 68, 45, 99, 43, 21, 3, 92, 69, 95, 8, 30, 84, 45, 10, 64, 95, 49, 60, 60, 45, 30, 94, 36, 17, 97, 90, 39, 4, 97, 76, 28, 80, 92, 5, 66, 20, 69, 95, 43, 95, 35, 30, 67, 67, 87, 36, 44, 11, 83, 62, 73, 42, 80, 20, 99, 79, 46, 1, 75, 85, 24, 5, 84, 47, 78, 91, 91, 38, 74, 16, 31, 96, 37, 60, 69, 12, 96, 2, 5, 83, 24, 67, 42, 7, 67, 94, 77, 34, 6, 75, 2, 61, 37, 15, 11, 65, 13, 63, 39, 42, 93, 22, 12, 89, 58, 98, 28, 69, 13, 98, 68, 34, 13, 93, 56, 85, 28, 92, 45, 84, 79, 70, 12, 27, 85, 1, 86, 94, 57, 64, 30, 75, 78, 49, 91, 19, 94, 77, 34, 40, 15, 64, 26, 34, 31, 70, 65, 34, 65, 7, 73, 61, 8, 23, 82, 55, 78, 36, 93, 10, 29, 64, 42, 99, 34, 91, 17, 33, 98, 45, 44, 74, 98, 60, 76, 6, 44, 73, 11, 13, 11, 73, 92, 55, 90, 3, 54, 23, 75, 28, 36, 82, 89, 84, 6, 39, 31, 39, 98, 34, 61, 21, 93, 48, 71, 80, 7, 46, 76, 71, 17, 7, 91, 6, 22, 76, 70, 27, 98, 35, 29, 69, 93, 42, 81, 62, 46, 87, 47, 51, 66, 2, 60, 3, 76, 68, 68, 74, 70, 3, 89, 18, 2, 57, 74, 79, 97, 16, 5, 73, 19, 90, 49, 6, 41, 88, 83, 34, 63, 52, 84, 14, 19, 76, 78, 88, 19, 92, 90, 34, 16, 69, 45, 85, 30, 71, 16, 77, 30, 43, 65, 85, 66, 11, 2, 72, 3, 83, 84, 14, 86, 90, 74, 79, 35, 33, 29, 78, 9, 92, 35, 64, 32, 30, 66, 9, 65, 30, 85, 81, 44, 95, 41, 22, 16, 28, 75, 63, 72, 23, 5, 73, 24, 89, 80, 25, 40, 88, 62, 3, 68, 6, 80, 6, 39, 17, 76, 24, 78, 6, 90, 79, 38, 44, 78, 85, 29, 48, 25, 75, 27, 76, 92, 19, 93, 21, 61, 56, 13, 64, 92, 52, 77, 12, 33, 77, 41, 75, 86, 29, 34, 65, 38, 66, 17, 15, 95, 50, 87, 52, 64, 72, 73, 6, 26, 80, 71, 8, 86, 1, 23, 67, 10, 72, 89, 9, 95, 60, 20, 46, 64, 99, 34, 46, 65, 14, 54, 93, 84, 4, 13, 86, 12, 26, 68, 56, 33, 83, 12, 93, 42, 74, 9, 99, 62, 22, 20, 83, 75, 13, 71, 96, 53, 96, 41, 8, 15, 76, 97, 55, 8, 78, 85, 57, 79, 30, 87, 17, 46, 62, 85, 14, 70, 63, 82, 28, 46, 96, 35, 89, 6, 9, 27, 44, 86, 93, 28, 9, 97, 73, 14, 7, 84, 64, 15, 62, 14, 17, 88, 92, 82, 11, 47, 63, 73, 13, 94, 98, 88, 15, 37, 38, 11, 2, 74, 20, 73, 94, 26, 96, 64, 56, 80, 53, 48, 85, 85, 35, 15, 90, 63, 9, 42, 99, 81, 97, 26, 94, 32, 24, 96, 61, 38, 18, 57, 22, 76, 7, 5, 43, 55, 97, 74, 35, 99, 86, 24, 25, 8, 60, 75, 18, 61, 14, 97, 52, 64, 97, 45, 29, 69, 91, 43, 40, 99, 58, 72, 73, 70, 45, 5, 97, 37, 89, 77, 32, 92, 94, 6, 33, 72, 64, 35, 75, 14, 32, 99, 64, 54, 78, 1, 92, 35, 30, 71, 11, 48, 82, 61, 49, 12, 46, 75, 54, 52, 33, 92, 24, 11, 72, 72, 16, 17, 57, 72, 68, 46, 15, 85, 58, 74, 55, 54, 87, 97, 44, 94, 16, 84, 57, 56, 96, 33, 79, 7, 70, 50, 23, 98, 91, 6, 62, 51, 73, 68, 17, 83, 93, 56, 15, 81, 99, 88, 15, 13, 93, 53, 48, 69, 2, 14, 83, 86, 39, 4, 54, 69, 52, 42, 60, 79, 92, 38, 68, 90, 48, 77, 46, 77, 16, 89, 3, 96, 77, 11, 77, 23, 73, 98, 35, 3, 1, 97, 48, 62, 36, 74, 13, 93, 19, 71, 23, 70, 64, 64, 14, 71, 86, 98, 20, 95, 1, 97, 30, 92, 16, 98, 63, 94, 56, 90, 49, 94, 28, 88, 43, 84, 38, 74, 83, 62, 4, 98, 63, 69, 0, 0, 21, 21, 1, 10, 1, 0, 0, 0, 0, 0, 0];
-
-#[derive(Clone, Copy, Debug, PartialEq)]
-enum DroidStatus {
-	Wall = 0,
-	Moved = 1,
-	Oxygen = 2,
-}
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum MapTile {
-	Wall = 0,
-	Space = 1,
-	Goal = 2,
+	Space,
+	Goal,
 }
 
-type Tiles = BTreeMap<Coords, MapTile>;
-type Path = VecDeque<Coords>;
-
-struct DroidController {
-	dir: Direction,
-	coord: Coords,
-	map: Tiles,
-	path: Path,
-	done: bool,
+struct Droid {
+	intcode: IntCodeVM,
 }
 
-impl DroidStatus {
-	pub fn convert_int(value: NumType) -> DroidStatus {
-		match value {
-			0 => DroidStatus::Wall,
-			1 => DroidStatus::Moved,
-			2 => DroidStatus::Oxygen,
-			_ => unreachable!()
-		}
-	}
-}
+impl Protocol for Droid {
+	type Cell = MapTile;
 
-impl MapTile {
-	pub fn from_status(status: DroidStatus) -> MapTile {
-		match status {
-			DroidStatus::Wall => MapTile::Wall,
-			DroidStatus::Moved => MapTile::Space,
-			DroidStatus::Oxygen => MapTile::Goal
-		}
+	fn start(&mut self) -> Result<MapTile> {
+		Ok(MapTile::Space)
 	}
-	pub fn to_pixel(self) -> Pixel{
-		return match self {
-			MapTile::Wall => Pixel::White,
-			MapTile::Space => Pixel::Black,
-			MapTile::Goal => Pixel::Star
-		}
-	}
-}
 
-impl DroidController {
-	fn new() -> DroidController {
-		let mut path = VecDeque::new();
-		//Try and move around area and then start from there!
-		//It shouldn't matter tho, as long as we have a way to go!
-		path.push_front(Coords{ x:0, y:-1});
-		path.push_front(Coords{ x:0, y:0});
-		path.push_front(Coords{ x:0, y:1});
-		path.push_front(Coords{ x:0, y:0});
-		path.push_front(Coords{ x:1, y:0});
-		path.push_front(Coords{ x:0, y:0});
-		path.push_front(Coords{ x:-1, y:0});
-		DroidController {
-			//Start facing north I guess?
-			dir: Direction::North,
-			coord: Coords {
-				x: 0,
-				y: 0,
-			},
-			map: BTreeMap::new(),
-			path,
-			done: false,
+	fn step(&mut self, direction: Direction) -> Result<Step<MapTile>> {
+		if self.intcode.is_stopped() {
+			return Err(eyre!("The droid's program stopped before the map was finished"));
 		}
-	}
-
-	fn next_dir(&mut self) -> Direction {
-		if !self.done {
-			self.dir = self
-				.coord
-				.turn_to_face(&self.path.pop_front().unwrap());
+		self.intcode.push_input(direction.to_command());
+		self.intcode.run_all()?;
+		match self.intcode.output().pop() {
+			Some(0) => Ok(Step::Blocked),
+			Some(1) => Ok(Step::Moved(MapTile::Space)),
+			Some(2) => Ok(Step::Moved(MapTile::Goal)),
+			other => Err(eyre!("The droid said {:?}, not where it got to", other)),
 		}
-		self.dir
 	}
 
-	fn make_path(&mut self) {
-		if let Some(path) = breadth_search(
-			&self.map, self.coord,
-			|_, tle| {
-				tle == None
-			},
-		) {
-			//println!("Have a path! {:?} from {:?} facing {:?}", path, self.coord, self.dir);
-			//thread::sleep(Duration::from_millis(1000));
-			self.path = path;
-		} else {
-			self.done = true;
+	fn cell_type(&self, cell: &MapTile) -> CellType {
+		match cell {
+			MapTile::Space => CellType::Space,
+			MapTile::Goal => CellType::Goal('*'),
 		}
 	}
-	fn move_result(&mut self, tile: MapTile) {
-		let coords = self.coord.move_direction(self.dir);
-		if tile != MapTile::Wall {
-			self.coord = coords;
-		}
-		self.map.insert(coords, tile);
-		if self.path.is_empty() {
-			self.make_path();
-		}
-	}
-	fn bfs_to_oxygen(&self) -> Option<VecDeque<Coords>> {
-		let start = Coords{ x:0, y:0};
-		// search for goal tile
-		breadth_search(&self.map, start, |_, tle| {
-			tle == Some(&MapTile::Goal)
-		})
-	}
+}
 
-	fn make_map(&mut self, mut intcode: IntCodeVM) {
-		while !self.done {
-			if intcode.is_stopped(){
-				panic!("Cannot continue with dead vm?")
-			}
-			intcode.push_input(self.next_dir().to_command());
-			intcode.run_all();
-			self.move_result(
-				MapTile::from_status(
-					DroidStatus::convert_int(
-						intcode.output().borrow_mut().pop_front().unwrap()
-					)
-				)
-			);
-		}
-	}
+/// Everything the droid found out: how far the oxygen system is from where it started, how long the oxygen takes to
+/// get everywhere, and the map it made on the way
+pub struct Survey {
+	pub to_oxygen: usize,
+	pub fill_time: usize,
+	pub map: ImageLayer,
+}
 
-	fn deepest_depth(&self) -> String
-	{
-		let directions = vec![
-			Direction::North,
-			Direction::East,
-			Direction::South,
-			Direction::West,
-		];
-		let start = self.map.iter().find_map(|(&coord, tile)| {
-			if tile == &MapTile::Goal {
-				Some(coord)
-			} else {
-				None
-			}
-		}).unwrap();
-		let mut depth = 0;
-		let mut path = VecDeque::new();
-		let mut replace_path = VecDeque::new();
-		let mut visited = HashSet::new();
+pub fn only_impl(mem: Vec<NumType>) -> Result<Survey> {
+	let mut explorer = Explorer::new(Droid { intcode: IntCodeVM::new(mem) });
+	explorer.explore()?;
+	let oxygen = explorer
+		.cells()
+		.iter()
+		.find_map(|(&coords, &tile)| if tile == MapTile::Goal { Some(coords) } else { None })
+		.ok_or_else(|| eyre!("The droid never found the oxygen system"))?;
+	//And return the result! The oxygen spreads a step a minute, so part 2 is as far as anywhere is from it
+	let from_oxygen = explorer.distances(oxygen);
+	let to_oxygen = from_oxygen[&Default::default()];
+	let fill_time = from_oxygen.values().copied().max().unwrap_or(0);
+	Ok(Survey { to_oxygen, fill_time, map: explorer.to_map().to_image() })
+}
 
-		visited.insert(start);
-		path.push_back(start);
+pub struct Code;
 
-		//While we have somewhere to try to go
-		while !path.is_empty(){
-			while let Some(curr_pos) = path.pop_front() {
-				//Look for it in the map
-				for &direction in directions.iter() {
-					let neighbor = curr_pos.move_direction(direction);
-					if !visited.contains(&neighbor) {
-						visited.insert(neighbor);
-						if self.map.get(&neighbor) == Some(&MapTile::Space) {
-							replace_path.push_back(neighbor);
-						}
-					}
-				}
-			}
-			depth += 1;
-			std::mem::swap(&mut path, &mut replace_path);
-		}
-		(depth - 1).to_string()
-	}
-}
+pub const SOLUTION: Solution = Solution::new(2019, 15, "Oxygen System", &Single(Code));
 
-fn breadth_search<T>(map: &Tiles, start: Coords, is_goal: T) -> Option<Path>
-	where T: Fn(Coords, Option<&MapTile>) -> bool
-{
-	let directions = vec![
-		Direction::North,
-		Direction::East,
-		Direction::South,
-		Direction::West,
-	];
+impl SinglePart for Code {
+	type Input = Memory;
 
-	let mut path = VecDeque::new();
-	let mut visited = HashSet::new();
-	visited.insert(start);
-	path.push_back(start);
-	let mut joined_to: HashMap<Coords, Coords> = HashMap::new();
-	//While we have somewhere to try to go
-	while let Some(curr_pos) = path.pop_front() {
-		//Look for it in the map
-		let curr_tile = map.get(&curr_pos);
-		if is_goal(curr_pos, curr_tile) {
-			//If it is the goal, work out the paths based on what is joined to what!
-			let mut final_path = VecDeque::new();
-			let mut back_curr = curr_pos.clone();
-			while let Some(&curr) = joined_to.get(&back_curr) {
-				final_path.push_front(back_curr);
-				back_curr = curr;
-			}
-			return Some(final_path);
-		} else {
-			//Work out if we are hitting a wall
-			let mut cont = true;
-			if let Some(tile) = curr_tile {
-				match tile {
-					MapTile::Wall => { cont = false }
-					_ => {}
-				}
-			}
-			//If we aren't hitting a wall
-			if cont {
-				for &direction in directions.iter() {
-					let next = curr_pos.move_direction(direction);
-					if !visited.contains(&next) {
-						//If we haven't seen this tile before...
-						visited.insert(next);
-						path.push_back(next);
-						joined_to.insert(next, curr_pos);
-					}
-				}
-			}
-		}
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		Ok(memory_from_str(input)?)
 	}
-	None
-}
 
-pub fn only_impl(mem: Vec<NumType>) -> String {
-	let intcode_vm = IntCodeVM::new(mem);
-	let mut robot = DroidController::new();
-	robot.make_map(intcode_vm);
-	//Let's print the map!
-	let mut hashmap:HashMap<Coords, Pixel> = HashMap::new();
-	for entry in robot.map.iter(){
-		hashmap.insert(entry.0.clone(), entry.1.to_pixel());
+	fn run(&self, input: &Self::Input) -> Result<(Answer, Option<Answer>)> {
+		let survey = only_impl(input.clone())?;
+		Ok((survey.to_oxygen.into(), Some(survey.fill_time.into())))
 	}
-	println!("{}",ImageLayer::from_hashmap(hashmap).to_string());
-	//And return the result!
-	let fmt = format!(
-		"Part 1:\t{}\nPart 2:\t{}\n",
-		robot.bfs_to_oxygen().unwrap().len().to_string(),
-		robot.deepest_depth()
-	);
-	return fmt;
 }
 
-pub struct Code;
-
-impl SinglePart for Code {
-	fn run(&self) -> String {
-		return only_impl(Vec::from(DAY_15_DATA));
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::image_layer::Pixel;
+
+	#[test]
+	fn test_survey() {
+		let survey = only_impl(memory_from_str(include_str!("../../inputs/2019/Day15.txt")).unwrap()).unwrap();
+		assert_eq!((survey.to_oxygen, survey.fill_time), (300, 312));
+		// The oxygen system is drawn in, once
+		assert_eq!(survey.map.pixels.iter().flatten().filter(|&&pixel| pixel == Pixel::LowP).count(), 1);
 	}
 }
-
-/*
- █████ ███████ ███████ ███ ███ ███████ █
-█     █       █       █   █   █       █ █
-█ █ █ █████ █ █ █████ █ █ █ ███ █ ███ █ █
-█ █ █   █   █ █ █       █   █   █ █   █ █
-█ █ ███ █ ███ █ █████ █████ █ ███ ███ █ █
-█ █   █   █ █ █     █ █   █ █ █ █   █   █
-█ ███ █████ █ █████ ███ █ ███ █ ███ ███ █
-█   █     █ █     █ █   █   █ █   █ █   █
- ████████ █ █████ █ █ ██ ██ █ █ █ █ █ ██
-█         █         █   █ █     █ █ █   █
-█ █████████ ███████████ █ ███████ █ ███ █
-█ █   █     █         █ █ █     █ █   █ █
-█ █ █ ███████ ███████ █ █ █ ███ ██ ██ █ █
-█   █ █   █   █     █ █ █ █   █   █   █ █
-█ ███ █ █ █ █████ █ █ █ █ ███ ███ █ ███ █
-█   █   █ █       █ █       █   █ █   █ █
- ██ █████ █ █████████████ █████ █ █ █ ██
-█ █ █   █   █           █ █     █ █ █   █
-█ █ █ ███████ █████████ ███ █████ █████ █
-█ █ █ █       █ █     █   █ █   █       █
-█ █ █ █ ███████ █ █ ██ ██ █ ███ ███████ █
-█ █ █ █   █       █ █ █   █ █     █     █
-█ █ █ ███ ███ █████ █ █ ███ █ █████ ████
-█       █   █     █ █   █   █     █   █ █
-█ █████████ █████ █ █████ ███ ███ ███ █ █
-█ █         █   █ █       █   █ █ █   █ █
-█ █ █████████ ███ █████ ███ ███ █ █ ███ █
-█ █   █ █     █   █   █ █   █   █   █   █
- ████ █ █ █ ███ ███ ███ █ █████ ████ ██ █
-█   █ █   █ █   █   █   █     █     █   █
-█ █ █ █████ █ ███ ███ ███████ ███ █ █ █ █
-█ █   █   █       █   █     █   █ █   █ █
-█ █████ █ █████████ ███ ████ ██ █ █████ █
-█ █     █       █   █   █   █   █   █   █
-█ █████ ███████ █ ███ █ █ █ █ ███ █ ███ █
-█     █       █ █ █   █ █ █   █   █   █ █
- ████ ███████ █ █ █ ███ █ █████ █████ █ █
-█ █   █   █   █   █ █ █ █ █     █   █ █ █
-█ █ ███ █ █ ███████ █ █ █ ███████ █ █ █ █
-█       █   █         █           █   █*█
- ███████ ███ █████████ ███████████ ███ █
- */
\ No newline at end of file
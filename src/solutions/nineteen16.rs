@@ -1,23 +1,25 @@
-use crate::AoCDay;
+use color_eyre::eyre::{eyre, Result};
 
-pub const DAY_16_DATA: &str = include_str!("../../inputs/2019/Day16.txt");
+use crate::{Answer, AoCDay, Parts};
+use crate::registry::Solution;
 
 pub struct Code;
 
-fn parse_input(input: &str) -> Vec<u8> {
+pub const SOLUTION: Solution = Solution::new(2019, 16, "Flawed Frequency Transmission", &Parts(Code));
+
+fn parse_input(input: &str) -> Result<Vec<u8>> {
 	return input
+		.trim()
 		.chars()
-		.map(|c| c.to_string().parse::<u8>().unwrap())
+		.map(|c| c.to_digit(10).map(|d| d as u8).ok_or_else(|| eyre!("{:?} is not a digit", c)))
 		.collect();
 }
 
-pub fn part_1_impl(input: &str) -> String {
-	let inp = parse_input(input);
+pub fn part_1_impl(inp: Vec<u8>) -> String {
 	get_digits_after_phases(8, 100, inp, 1, 0)
 }
 
-pub fn part_2_impl(input: &str) -> String {
-	let inp = parse_input(input);
+pub fn part_2_impl(inp: Vec<u8>) -> String {
 	let mut num_dropped: usize = 0;
 	for i in 0..7 {
 		num_dropped = (num_dropped * 10) + (inp[i] as usize);
@@ -71,12 +73,18 @@ fn perform_phase_normal(signal: &mut Vec<u8>) {
 }
 
 impl AoCDay for Code {
-	fn part1(&self) -> String {
-		return part_1_impl(DAY_16_DATA);
+	type Input = Vec<u8>;
+
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		parse_input(input)
+	}
+
+	fn part1(&self, input: &Self::Input) -> Result<Answer> {
+		return Ok(part_1_impl(input.clone()).into());
 	}
 
-	fn part2(&self) -> String {
-		return part_2_impl(DAY_16_DATA);
+	fn part2(&self, input: &Self::Input) -> Result<Answer> {
+		return Ok(part_2_impl(input.clone()).into());
 	}
 }
 
@@ -86,7 +94,7 @@ mod tests {
 
 	#[test]
 	fn test_example_a() {
-		let inp = parse_input("12345678");
+		let inp = parse_input("12345678").unwrap();
 		assert_eq!(
 			get_digits_after_phases(8, 4, inp, 1, 0),
 			"01029498");
@@ -94,7 +102,7 @@ mod tests {
 
 	#[test]
 	fn test_example_b() {
-		let inp = parse_input("80871224585914546619083218645595");
+		let inp = parse_input("80871224585914546619083218645595").unwrap();
 		assert_eq!(
 			get_digits_after_phases(8, 100, inp, 1, 0),
 			"24176176");
@@ -102,7 +110,7 @@ mod tests {
 
 	#[test]
 	fn test_example_c() {
-		let inp = parse_input("19617804207202209144916044189917");
+		let inp = parse_input("19617804207202209144916044189917").unwrap();
 		assert_eq!(
 			get_digits_after_phases(8, 100, inp, 1, 0),
 			"73745418");
@@ -110,7 +118,7 @@ mod tests {
 
 	#[test]
 	fn test_example_d() {
-		let inp = parse_input("69317163492948606335995924319873");
+		let inp = parse_input("69317163492948606335995924319873").unwrap();
 		assert_eq!(
 			get_digits_after_phases(8, 100, inp, 1, 0),
 			"52432133");
@@ -118,7 +126,7 @@ mod tests {
 
 	#[test]
 	fn test_example_e() {
-		let inp = parse_input("03036732577212944063491565474664");
+		let inp = parse_input("03036732577212944063491565474664").unwrap();
 		assert_eq!(
 			get_digits_after_phases(8, 100, inp, 10000, 0303673),
 			"84462026");
@@ -1,12 +1,41 @@
-use crate::AoCDay;
-use crate::map::{TwoDMap, Graph, CellType};
+use color_eyre::eyre::{eyre, Result};
+
+use crate::{Answer, AoCDay, Parts};
+use crate::registry::Solution;
+use crate::map::TwoDMap;
+use crate::graph::Graph;
 use crate::coords::Coords;
-use crate::direction::Direction::{North, East, South, West};
 
 pub struct Code;
 
-pub const DAY_18_DATA: &str = include_str!("../../inputs/2019/Day18.txt");
-pub const DAY_18_P2_DATA: &str = include_str!("../../inputs/2019/Day18-2.txt");
+pub const SOLUTION: Solution = Solution::new(2019, 18, "Many-Worlds Interpretation", &Parts(Code));
+
+/// Part 2 swaps the middle of the vault for four separate robots:
+/// ```text
+/// ...      4#1
+/// .@.  =>  ###
+/// ...      3#2
+/// ```
+pub fn split_vault(input: &str) -> Result<String> {
+	let mut grid: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+	let (x, y) = grid
+		.iter()
+		.enumerate()
+		.find_map(|(y, row)| row.iter().position(|&c| c == '@').map(|x| (x, y)))
+		.ok_or_else(|| eyre!("The vault needs an entrance"))?;
+	// Everything round the entrance gets replaced, so it can't be on the edge
+	if x == 0 || y == 0 || y + 1 >= grid.len() || grid[y - 1..=y + 1].iter().any(|row| row.len() <= x + 1) {
+		return Err(eyre!("The entrance at {},{} is on the edge of the vault", x, y));
+	}
+	grid[y - 1][x - 1..=x + 1].copy_from_slice(&['4', '#', '1']);
+	grid[y][x - 1..=x + 1].copy_from_slice(&['#', '#', '#']);
+	grid[y + 1][x - 1..=x + 1].copy_from_slice(&['3', '#', '2']);
+	Ok(grid
+		.iter()
+		.map(|row| row.iter().collect::<String>())
+		.collect::<Vec<String>>()
+		.join("\n"))
+}
 
 fn parse_maze(input: &str) -> (TwoDMap, Vec<Coords>) {
 	let uppercase = (b'A'..=b'Z')
@@ -28,15 +57,40 @@ fn parse_maze(input: &str) -> (TwoDMap, Vec<Coords>) {
 }
 
 impl AoCDay for Code{
-	fn part1(&self) -> String {
-		let maze = parse_maze(DAY_18_DATA);
-		let graph = Graph::from_two_d_map(maze.0);
-		graph.traverse().to_string()
+	type Input = String;
+
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		Ok(input.to_string())
+	}
+
+	fn part1(&self, input: &Self::Input) -> Result<Answer> {
+		collect_keys(input)
 	}
 
-	fn part2(&self) -> String {
-		let maze = parse_maze(DAY_18_P2_DATA);
-		let graph = Graph::from_two_d_map(maze.0);
-		graph.traverse().to_string()
+	fn part2(&self, input: &Self::Input) -> Result<Answer> {
+		collect_keys(&split_vault(input)?)
 	}
-}
\ No newline at end of file
+}
+
+fn collect_keys(vault: &str) -> Result<Answer> {
+	let maze = parse_maze(vault);
+	let graph = Graph::from_two_d_map(maze.0);
+	match graph.traverse() {
+		usize::MAX => Err(eyre!("The robots couldn't find a way to collect every key")),
+		steps => Ok(steps.into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_split_vault() {
+		assert_eq!(
+			split_vault(include_str!("../../inputs/2019/Day18.txt")).unwrap().trim(),
+			include_str!("../../inputs/2019/Day18-2.txt").trim()
+		);
+		assert!(split_vault("#@.\n...\n...").is_err());
+	}
+}
@@ -1,11 +1,14 @@
-use crate::AoCDay;
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use crate::{Answer, AoCDay, Parts};
+use crate::registry::Solution;
 
 pub struct Code;
 
-pub const DAY_22_DATA: &str = include_str!("../../inputs/2019/Day22.txt");
+pub const SOLUTION: Solution = Solution::new(2019, 22, "Slam Shuffle", &Parts(Code));
 
 #[derive(Clone, Copy, Debug)]
-enum ShuffleAction {
+pub enum ShuffleAction {
 	Stack,
 	Cut(i64),
 	Increment(i64),
@@ -127,22 +130,20 @@ fn collapse(o_actions: &Vec<ShuffleAction>, number_cards: &i64) -> Vec<ShuffleAc
 	return actions;
 }
 
-fn parse(input: &str) -> Vec<ShuffleAction> {
+fn parse(input: &str) -> Result<Vec<ShuffleAction>> {
 	let mut actions: Vec<ShuffleAction> = Vec::with_capacity(input.lines().count());
-	input
-		.lines()
-		.for_each(|ln| {
-			if ln.starts_with("deal into new stack") {
-				actions.push(ShuffleAction::Stack)
-			} else if ln.starts_with("cut ") {
-				actions.push(ShuffleAction::Cut(ln.replace("cut ", "").parse::<i64>().unwrap()));
-			} else if ln.starts_with("deal with increment ") {
-				actions.push(ShuffleAction::Increment(ln.replace("deal with increment ", "").parse::<i64>().unwrap()));
-			} else {
-				panic!("No actions readable from {}", ln)
-			}
-		});
-	return actions;
+	for ln in input.lines() {
+		if ln.starts_with("deal into new stack") {
+			actions.push(ShuffleAction::Stack)
+		} else if ln.starts_with("cut ") {
+			actions.push(ShuffleAction::Cut(ln.replace("cut ", "").trim().parse::<i64>().wrap_err_with(|| format!("Bad cut in {}", ln))?));
+		} else if ln.starts_with("deal with increment ") {
+			actions.push(ShuffleAction::Increment(ln.replace("deal with increment ", "").trim().parse::<i64>().wrap_err_with(|| format!("Bad increment in {}", ln))?));
+		} else {
+			return Err(eyre!("No actions readable from {}", ln));
+		}
+	}
+	return Ok(actions);
 }
 
 fn repeat_n_times(actions: Vec<ShuffleAction>, num_times: &i64, num_cards: &i64) -> Vec<ShuffleAction>{
@@ -177,24 +178,30 @@ fn repeat_n_times(actions: Vec<ShuffleAction>, num_times: &i64, num_cards: &i64)
 }
 
 impl AoCDay for Code {
-	fn part1(&self) -> String {
+	type Input = Vec<ShuffleAction>;
+
+	fn parse(&self, input: &str) -> Result<Self::Input> {
+		parse(input)
+	}
+
+	fn part1(&self, input: &Self::Input) -> Result<Answer> {
 		let mut card_num: i64 = 2019;
 		let num_cards: i64 = 10007;
-		let actions = collapse(&parse(DAY_22_DATA), &num_cards);
+		let actions = collapse(input, &num_cards);
 		actions
 			.iter()
 			.for_each(|action| {
 				card_num = action.apply(card_num, &num_cards);
 			});
-		return card_num.to_string();
+		return Ok(card_num.into());
 	}
 
-	fn part2(&self) -> String {
+	fn part2(&self, input: &Self::Input) -> Result<Answer> {
 		let num_cards: i64 = 119315717514047;
 		let num_times: i64 = 101741582076661;
 		let mut card_num: i64 = 2020;
 		
-		let mut actions = collapse(&parse(DAY_22_DATA), &num_cards);
+		let mut actions = collapse(input, &num_cards);
 		actions = repeat_n_times(actions, &num_times, &num_cards);
 		actions
 			.iter()
@@ -215,6 +222,6 @@ impl AoCDay for Code {
 					}
 				}
 			});
-		return card_num.to_string();
+		return Ok(card_num.into());
 	}
 }
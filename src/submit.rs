@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use color_eyre::eyre::{eyre, Result};
+use structopt::StructOpt;
+
+use aoc_2020::answers::{self, AnswerFile};
+use aoc_2020::guesses::History;
+use aoc_2020::site::{Outcome, Site};
+use aoc_2020::{Answer, Part};
+
+use crate::day::Day;
+use crate::run::solve;
+
+#[derive(StructOpt)]
+pub struct Submit {
+    /// The day then the part, e.g. `2019 13 1`. The day can be written any way `run` takes it
+    #[structopt(required = true, min_values = 2, max_values = 3)]
+    day_and_part: Vec<String>,
+    /// The year, if the day doesn't say
+    #[structopt(long, short)]
+    year: Option<u32>,
+    /// Send this instead of running the solution, for answers that have to be read off a picture
+    #[structopt(long, short)]
+    answer: Option<String>,
+    /// Where to read the puzzle input from, see `run`
+    #[structopt(long, short, parse(from_os_str))]
+    input: Option<PathBuf>,
+}
+
+impl Submit {
+    pub fn run(&self) -> Result<String> {
+        let (part, day) = self.day_and_part.split_last().unwrap();
+        let day = Day::parse(&day.join(" "), self.year)?;
+        let part: Part = part.parse().map_err(|_| eyre!("{:?} isn't a part, use 1 or 2", part))?;
+        if part == Part::Both {
+            return Err(eyre!("Answers are submitted one part at a time, use 1 or 2"));
+        }
+        let answer = match &self.answer {
+            Some(answer) => answer.trim().to_string(),
+            None => {
+                let answers = solve(day, part, self.input.as_deref())?;
+                let answer = match part {
+                    Part::Part1 => answers.part1,
+                    _ => answers.part2,
+                };
+                let answer = answer
+                    .ok_or_else(|| eyre!("{} day {} doesn't have an answer for {}", day.year(), day.day(), part))?;
+                if answer.is_multiline() {
+                    return Err(eyre!("This answer needs reading by eye, pass what it says with --answer:\n{}", answer));
+                }
+                answer.to_string()
+            }
+        };
+
+        if let Some(recorded) = AnswerFile::load(day.year())?.get(day.day(), part) {
+            return Err(eyre!("{} day {} {} is already recorded as solved with {}", day.year(), day.day(), part, recorded));
+        }
+        let mut history = History::load(day.year())?;
+        history.check(day.day(), part, &answer)?;
+
+        let outcome = Site::from_env()?.submit(day.year(), day.day(), part, &answer)?;
+        history.record(day.day(), part, &answer, &outcome)?;
+        history.save()?;
+        Ok(match outcome {
+            Outcome::Correct => {
+                let recorded = answer.parse().map_or_else(|_| Answer::Text(answer.clone()), Answer::Int);
+                answers::record(day.year(), day.day(), part, &recorded)?;
+                format!("{} is right! Saved it to {}", answer, answers::default_path(day.year()).display())
+            }
+            outcome if outcome.is_wrong() => format!("{} is {}", answer, outcome),
+            outcome => format!("{} wasn't judged: {}", answer, outcome),
+        })
+    }
+}
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use aoc_2020::Answer;
+
+/// A markdown table, the same shape as the ones in the README
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Table {
+        Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        debug_assert_eq!(row.len(), self.headers.len());
+        self.rows.push(row);
+    }
+
+    pub fn render(&self) -> String {
+        let widths: Vec<usize> = (0..self.headers.len())
+            .map(|column| {
+                self.rows
+                    .iter()
+                    .map(|row| row[column].chars().count())
+                    .chain(std::iter::once(self.headers[column].chars().count()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let line = |cells: &[String]| {
+            let padded: Vec<String> = cells
+                .iter()
+                .zip(widths.iter())
+                .map(|(cell, &width)| format!("{:width$}", cell, width = width))
+                .collect();
+            format!("| {} |", padded.join(" | "))
+        };
+        let divider: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+        let mut lines = vec![line(&self.headers), line(&divider)];
+        lines.extend(self.rows.iter().map(|row| line(row)));
+        lines.join("\n")
+    }
+}
+
+/// How a day is written in tables, e.g. `2019 07`
+pub fn day_label(year: u32, day: u32) -> String {
+    format!("{} {:02}", year, day)
+}
+
+/// Answers that need several lines don't fit in a cell, so just say what they are
+pub fn answer_cell(answer: &Option<Answer>) -> String {
+    match answer {
+        None => "N/A".to_string(),
+        Some(Answer::Image(image)) => format!("[image {}x{}]", image.pixels.first().map_or(0, |r| r.len()), image.pixels.len()),
+        Some(answer) if answer.is_multiline() => format!("[{} lines]", answer.to_string().lines().count()),
+        Some(answer) => answer.to_string(),
+    }
+}
+
+/// Durations in the same style Criterion reports them, e.g. `1.6073 us`
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos() as f64;
+    let (value, unit) = if nanos < 1_000.0 {
+        (nanos, "ns")
+    } else if nanos < 1_000_000.0 {
+        (nanos / 1_000.0, "us")
+    } else if nanos < 1_000_000_000.0 {
+        (nanos / 1_000_000.0, "ms")
+    } else {
+        (nanos / 1_000_000_000.0, "s")
+    };
+    // Five significant figures, however big the number in front of the unit is
+    let decimals = if value < 10.0 {
+        4
+    } else if value < 100.0 {
+        3
+    } else {
+        2
+    };
+    format!("{:.*} {}", decimals, value, unit)
+}
+
+/// Byte counts in binary units, e.g. `1.50 KiB`
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut table = Table::new(&["Day", "Part 1"]);
+        table.push(vec![day_label(2019, 2), "1".to_string()]);
+        assert_eq!(
+            table.render(),
+            "| Day     | Part 1 |\n\
+             | ------- | ------ |\n\
+             | 2019 02 | 1      |"
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(922)), "922.00 ns");
+        assert_eq!(format_duration(Duration::from_nanos(1_607_300)), "1.6073 ms");
+        assert_eq!(format_duration(Duration::from_micros(33_630)), "33.630 ms");
+        assert_eq!(format_duration(Duration::from_secs(2)), "2.0000 s");
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.50 KiB");
+        assert_eq!(format_bytes(3 << 20), "3.00 MiB");
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use color_eyre::eyre::{eyre, Result};
+
+use aoc_2020::registry::{Solution, Step};
+use aoc_2020::{Answers, Part};
+
+use crate::table::{format_bytes, format_duration, Table};
+
+/// One step of a day over several runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    pub label: &'static str,
+    pub min: Duration,
+    pub mean: Duration,
+    pub median: Duration,
+    /// The most the heap grew in any one run
+    pub peak_heap: usize,
+}
+
+impl Summary {
+    pub fn of(label: &'static str, steps: &[Step]) -> Summary {
+        let mut times: Vec<Duration> = steps.iter().map(|s| s.time).collect();
+        times.sort_unstable();
+        let middle = times.len() / 2;
+        let median = if times.len() % 2 == 0 {
+            (times[middle - 1] + times[middle]) / 2
+        } else {
+            times[middle]
+        };
+        Summary {
+            label,
+            min: times[0],
+            mean: times.iter().sum::<Duration>() / times.len() as u32,
+            median,
+            peak_heap: steps.iter().map(|s| s.peak_heap).max().unwrap_or(0),
+        }
+    }
+}
+
+/// Solve a day `repeats` times over, summarising each step. The answers come from the first run
+pub fn repeat(solution: &Solution, input: &str, part: Part, repeats: usize) -> Result<(Answers, Vec<Summary>)> {
+    if repeats == 0 {
+        return Err(eyre!("It needs running at least once"));
+    }
+    let (answers, first) = solution.timed(input, part)?;
+    let labels: Vec<&'static str> = first.iter().map(|s| s.label).collect();
+    let mut steps: BTreeMap<&'static str, Vec<Step>> = BTreeMap::new();
+    for step in first {
+        steps.entry(step.label).or_default().push(step);
+    }
+    for _ in 1..repeats {
+        for step in solution.timed(input, part)?.1 {
+            steps.entry(step.label).or_default().push(step);
+        }
+    }
+    let summaries = labels.iter().map(|label| Summary::of(label, &steps[label])).collect();
+    Ok((answers, summaries))
+}
+
+/// A table of each step. A single run just has the one time, more get the min, mean and median
+pub fn table(summaries: &[Summary], repeats: usize) -> Table {
+    if repeats == 1 {
+        let mut table = Table::new(&["Step", "Time", "Peak heap"]);
+        for s in summaries {
+            table.push(vec![s.label.to_string(), format_duration(s.mean), format_bytes(s.peak_heap)]);
+        }
+        table
+    } else {
+        let mut table = Table::new(&["Step", "Min", "Mean", "Median", "Peak heap"]);
+        for s in summaries {
+            table.push(vec![
+                s.label.to_string(),
+                format_duration(s.min),
+                format_duration(s.mean),
+                format_duration(s.median),
+                format_bytes(s.peak_heap),
+            ]);
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(millis: u64, peak_heap: usize) -> Step {
+        Step { label: "Part 1", time: Duration::from_millis(millis), peak_heap }
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::of("Part 1", &[step(4, 10), step(1, 30), step(10, 20)]);
+        assert_eq!(summary.min, Duration::from_millis(1));
+        assert_eq!(summary.mean, Duration::from_millis(5));
+        assert_eq!(summary.median, Duration::from_millis(4));
+        assert_eq!(summary.peak_heap, 30);
+        let even = Summary::of("Part 1", &[step(4, 0), step(2, 0), step(1, 0), step(10, 0)]);
+        assert_eq!(even.median, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn test_repeat() {
+        let solution = aoc_2020::registry::find(2019, 1).unwrap();
+        let (answers, summaries) = repeat(solution, "12\n14\n", Part::Both, 3).unwrap();
+        assert_eq!(answers.part1, Some(aoc_2020::Answer::Int(4)));
+        assert_eq!(summaries.iter().map(|s| s.label).collect::<Vec<_>>(), vec!["Parse", "Part 1", "Part 2"]);
+        assert!(repeat(solution, "12\n", Part::Both, 0).is_err());
+    }
+}
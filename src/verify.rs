@@ -0,0 +1,64 @@
+use color_eyre::eyre::{eyre, Result};
+use structopt::StructOpt;
+
+use aoc_2020::answers::{AnswerFile, Verdict};
+use aoc_2020::registry;
+
+use crate::run_all::run_solution;
+use crate::table::{day_label, Table};
+
+#[derive(StructOpt)]
+pub struct Verify {
+    /// Only check the solutions for this year
+    #[structopt(long, short)]
+    year: Option<u32>,
+}
+
+impl Verify {
+    pub fn run(&self) -> Result<String> {
+        let years = match self.year {
+            Some(year) => vec![year],
+            None => registry::years(),
+        };
+        let mut table = Table::new(&["Day", "Name", "Part 1", "Part 2"]);
+        let mut details = Vec::new();
+        let (mut passed, mut failed, mut missing) = (0, 0, 0);
+        for year in years {
+            let answers = AnswerFile::load(year)?;
+            for solution in registry::for_year(year) {
+                let verdicts = match run_solution(solution) {
+                    Ok((results, _)) => answers.verify(solution.day, &results),
+                    // Couldn't even get to the parts, so blame both of them
+                    Err(e) => (Verdict::Error(e.to_string()), Verdict::Error(e.to_string())),
+                };
+                let mut cells = vec![day_label(solution.year, solution.day), solution.name.to_string()];
+                for (part, verdict) in [(1, verdicts.0), (2, verdicts.1)] {
+                    match verdict {
+                        Verdict::Pass => passed += 1,
+                        Verdict::Missing => missing += 1,
+                        _ => failed += 1,
+                    }
+                    // Drawn answers don't fit in a cell, they go under the table instead
+                    if verdict.to_string().contains('\n') {
+                        cells.push("fail, see below".to_string());
+                        details.push(format!("{} part {}: {}", day_label(solution.year, solution.day), part, verdict));
+                    } else {
+                        cells.push(verdict.to_string());
+                    }
+                }
+                table.push(cells);
+            }
+        }
+        let mut output = vec![table.render()];
+        output.extend(details);
+        output.push(format!("{} passed, {} failed, {} missing", passed, failed, missing));
+        let output = output.join("\n\n");
+        if failed > 0 {
+            // Still show the table, the error is only there to make the exit code say it failed
+            println!("{}", output);
+            Err(eyre!("{} part(s) didn't match their recorded answers", failed))
+        } else {
+            Ok(output)
+        }
+    }
+}